@@ -1,8 +1,15 @@
 // snake case is just bad
-#![allow(dead_code, non_snake_case)]
+#![allow(non_snake_case)]
 
-use std::io::Write;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
+pub mod backend;
+pub mod color;
+pub mod grid;
+pub mod layout;
+pub mod theme;
+pub mod widgets;
 
 // static color/mod pairs for default ascii/ansi codes
 // colorCode (if any), mods, background (bool)   when called if background then add that color as background col
@@ -14,7 +21,28 @@ use std::io::Write;
 // /033[... doesn't work; use /x1b[...
 pub static CLEAR: &str = "\x1b[0m";
 pub static SHOW_CURSOR: &str = "\x1b[?25h";
-pub static HIDE_CURSOR: &str = "\x1b[?25l";
+
+// The number of terminal cells the text takes up
+// Wide characters (CJK, most emoji) take two cells, and combining marks/zero width joiners take none
+pub fn GetDisplayWidth (text: &str) -> usize {
+    text.width()
+}
+
+// The text cut down to the number of cells (never splitting a grapheme) and padded out with spaces to fill them,
+// for lining text up in columns
+//   e.g. FitToWidth("漢字 practice", 6) == "漢字 p"
+pub fn FitToWidth (text: &str, width: usize) -> String {
+    let mut fitted = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let size = GetDisplayWidth(grapheme);
+        if used + size > width {  break;  }
+        fitted.push_str(grapheme);
+        used += size;
+    }
+    fitted.push_str(&" ".repeat(width - used));
+    fitted
+}
 
 // * color, modifiers, is_background
 pub static EMPTY_MODIFIER_REFERENCE: &[&str] = &[];  // making a default static type is annoying
@@ -68,30 +96,7 @@ pub static REVERSE:   (Option <&str>, &[&str], bool) = (None    , &["7"], false)
 pub static HIDE:      (Option <&str>, &[&str], bool) = (None    , &["8"], false);
 
 
-// manages the global state for light/dark modes (handles basic colors switching around)
-// no support for RGB/custom color codes, only the default variants
-#[derive(Clone, PartialEq, Eq, Debug, Default, Hash, Copy)]
-pub enum ColorMode {
-    #[default] Dark,
-    Light,
-}
-
-impl ColorMode {
-    pub fn ToLight () {
-        unsafe {COLOR_MODE = ColorMode::Light};
-    }
-
-    pub fn ToDark () {
-        unsafe {COLOR_MODE = ColorMode::Dark};
-    }
-}
-
-// hopefully this will let full usage of colors while not worrying too much about light/dark mode
-// -- (basic but limited automatic support; not everything will look perfect by default)
-static mut COLOR_MODE: ColorMode = ColorMode::Dark;
-
-
-#[derive(Clone, Debug, Eq, PartialEq, Default, Hash, Copy)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash, Copy, serde::Serialize, serde::Deserialize)]
 // Different base ascii text modifiers (static constants)
 pub enum ColorType {
     Black,
@@ -142,8 +147,10 @@ pub enum ColorType {
     Reverse,
     Hide,
 
+    // 24-bit colors
     OnRGB (u8, u8, u8),
     Rgb(u8, u8, u8),
+    // colors from the 256 color palette
     OnANSI (u8),
     Ansi(u8),
 }
@@ -187,89 +194,6 @@ impl UniqueColor {
 impl ColorType {
     // Converts the color type into a unique color (static or dynamic)
     pub fn GetColor (&self) -> UniqueColor {
-        if unsafe { COLOR_MODE } == ColorMode::Dark {
-            self.GetDarkColor()
-        } else {
-            self.GetLightColor()
-        }
-    }
-
-    fn GetLightColor (&self) -> UniqueColor {
-        match self {
-            ColorType::Black =>   { UniqueColor::Static(BRIGHT_WHITE) },
-            ColorType::Red =>     { UniqueColor::Static(RED) },
-            ColorType::Green =>   { UniqueColor::Static(GREEN) },
-            ColorType::Yellow =>  { UniqueColor::Static(YELLOW) },
-            ColorType::Blue =>    { UniqueColor::Static(BLUE) },
-            ColorType::Magenta => { UniqueColor::Static(MAGENTA) },
-            ColorType::Cyan =>    { UniqueColor::Static(CYAN) },
-            ColorType::White =>   { UniqueColor::Static(BRIGHT_BLACK) },
-            ColorType::Default => { UniqueColor::Static(BRIGHT_DEFAULT) },
-
-            ColorType::BrightBlack =>   { UniqueColor::Static(WHITE) },
-            ColorType::BrightRed =>     { UniqueColor::Static(RED) },
-            ColorType::BrightGreen =>   { UniqueColor::Static(GREEN) },
-            ColorType::BrightYellow =>  { UniqueColor::Static(YELLOW) },
-            ColorType::BrightBlue =>    { UniqueColor::Static(BLUE) },
-            ColorType::BrightMagenta => { UniqueColor::Static(MAGENTA) },
-            ColorType::BrightCyan =>    { UniqueColor::Static(CYAN) },
-            ColorType::BrightWhite =>   { UniqueColor::Static(BLACK) },
-            ColorType::BrightDefault => { UniqueColor::Static(DEFAULT) },
-
-            ColorType::OnBlack => { UniqueColor::Static(ON_WHITE) },
-            ColorType::OnRed => { UniqueColor::Static(ON_RED) },
-            ColorType::OnGreen => { UniqueColor::Static(ON_GREEN) },
-            ColorType::OnYellow => { UniqueColor::Static(ON_YELLOW) },
-            ColorType::OnBlue => { UniqueColor::Static(ON_BLUE) },
-            ColorType::OnMagenta => { UniqueColor::Static(ON_MAGENTA) },
-            ColorType::OnCyan => { UniqueColor::Static(ON_CYAN) },
-            ColorType::OnWhite => { UniqueColor::Static(ON_BRIGHT_BLACK) },
-            ColorType::OnDefault => { UniqueColor::Static(ON_BRIGHT_DEFAULT) },
-
-            ColorType::OnBrightBlack => { UniqueColor::Static(ON_BRIGHT_WHITE) },
-            ColorType::OnBrightRed => { UniqueColor::Static(ON_RED) },
-            ColorType::OnBrightGreen => { UniqueColor::Static(ON_GREEN) },
-            ColorType::OnBrightYellow => { UniqueColor::Static(ON_YELLOW) },
-            ColorType::OnBrightBlue => { UniqueColor::Static(ON_BLUE) },
-            ColorType::OnBrightMagenta => { UniqueColor::Static(ON_MAGENTA) },
-            ColorType::OnBrightCyan => { UniqueColor::Static(ON_CYAN) },
-            ColorType::OnBrightWhite => { UniqueColor::Static(ON_BLACK) },
-            ColorType::OnBrightDefault => { UniqueColor::Static(ON_DEFAULT) },
-
-            // 24-bit? I think so but make sure it works
-            ColorType::Rgb(r, g, b) => {
-                let (mut rn, mut gn, mut bn) = (*r, *g, *b);
-                if rn > 128 {  rn -= 128;  }
-                if gn > 128 {  gn -= 128;  }
-                if bn > 128 {  bn -= 128;  }
-                UniqueColor::Dynamic((Some(format!("38;2;{};{};{}", rn, gn, bn)), EMPTY_MODIFIER_REFERENCE, false))
-            },
-            // background 24-bit? Make sure that's right
-            ColorType::OnRGB (r, g, b) => {
-                let (mut rn, mut gn, mut bn) = (*r, *g, *b);
-                if rn > 128 {  rn -= 128;  }
-                if gn > 128 {  gn -= 128;  }
-                if bn > 128 {  bn -= 128;  }
-                UniqueColor::Dynamic((Some(format!("48;2;{};{};{}", rn, gn, bn)), EMPTY_MODIFIER_REFERENCE, true))
-            },
-            ColorType::Ansi(index) => {
-                UniqueColor::Dynamic((Some(format!("38;5;{}", index)), EMPTY_MODIFIER_REFERENCE, false))
-            },
-            ColorType::OnANSI (index) => {
-                UniqueColor::Dynamic((Some(format!("48;5;{}", index)), EMPTY_MODIFIER_REFERENCE, true))
-            },
-
-            ColorType::Bold => { UniqueColor::Static(BOLD) },
-            ColorType::Dim => { UniqueColor::Static(DIM) },
-            ColorType::Italic => { UniqueColor::Static(ITALIC) },
-            ColorType::Underline => { UniqueColor::Static(UNDERLINE) },
-            ColorType::Blink => { UniqueColor::Static(BLINK) },
-            ColorType::Reverse => { UniqueColor::Static(REVERSE) },
-            ColorType::Hide => { UniqueColor::Static(HIDE) },
-        }
-    }
-
-    fn GetDarkColor (&self) -> UniqueColor {
         match self {
             ColorType::Black => { UniqueColor::Static(BLACK) },
             ColorType::Red => { UniqueColor::Static(RED) },
@@ -311,19 +235,18 @@ impl ColorType {
             ColorType::OnBrightWhite => { UniqueColor::Static(ON_WHITE) },
             ColorType::OnBrightDefault => { UniqueColor::Static(ON_BRIGHT_DEFAULT) },
 
-            // 24-bit? I think so but make sure it works
+            // 24-bit and 256 palette colors, brought down to what the terminal supports (see color::ColorSupport)
             ColorType::Rgb(r, g, b) => {
-                UniqueColor::Dynamic((Some(format!("38;2;{};{};{}", r, g, b)), EMPTY_MODIFIER_REFERENCE, false))
+                UniqueColor::Dynamic((Some(color::GetRgbCode((*r, *g, *b), false)), EMPTY_MODIFIER_REFERENCE, false))
             },
-            // background 24-bit? Make sure that's right
             ColorType::OnRGB (r, g, b) => {
-                UniqueColor::Dynamic((Some(format!("48;2;{};{};{}", r, g, b)), EMPTY_MODIFIER_REFERENCE, true))
+                UniqueColor::Dynamic((Some(color::GetRgbCode((*r, *g, *b), true)), EMPTY_MODIFIER_REFERENCE, true))
             },
             ColorType::Ansi(index) => {
-                UniqueColor::Dynamic((Some(format!("38;5;{}", index)), EMPTY_MODIFIER_REFERENCE, false))
+                UniqueColor::Dynamic((Some(color::GetIndexedCode(*index, false)), EMPTY_MODIFIER_REFERENCE, false))
             },
             ColorType::OnANSI (index) => {
-                UniqueColor::Dynamic((Some(format!("48;5;{}", index)), EMPTY_MODIFIER_REFERENCE, true))
+                UniqueColor::Dynamic((Some(color::GetIndexedCode(*index, true)), EMPTY_MODIFIER_REFERENCE, true))
             },
 
             ColorType::Bold => { UniqueColor::Static(BOLD) },
@@ -345,28 +268,22 @@ pub trait Colorize {
     // adds a set of modifiers/colors
     fn Colorizes (&self, colors: Vec <ColorType>) -> Colored;
 
-    // adds a single modifier/color
-    fn Colorize (&self, colors: ColorType) -> Colored;
+    // adds a theme's style (see theme::Theme)
+    fn Stylize (&self, style: &[ColorType]) -> Colored {
+        self.Colorizes(style.to_vec())
+    }
 }
 
 impl Colorize for &str {
     fn Colorizes (&self, colors: Vec <ColorType>) -> Colored {
         Colored::GetFromColorTypesStr(self, colors)
     }
-
-    fn Colorize (&self, color: ColorType) -> Colored {
-        Colored::GetFromColorTypesStr(self, vec![color])
-    }
 }
 
 impl Colorize for String {
     fn Colorizes (&self, colors: Vec <ColorType>) -> Colored {
         Colored::GetFromColorTypesStr(self.as_str(), colors)
     }
-
-    fn Colorize (&self, color: ColorType) -> Colored {
-        Colored::GetFromColorTypesStr(self.as_str(), vec![color])
-    }
 }
 
 
@@ -389,10 +306,6 @@ impl Colorize for Colored {
         }
         Colored::GetFromColorTypes(self, mods)
     }
-
-    fn Colorize (&self, color: ColorType) -> Colored {
-        Colored::GetFromColorTypes(self, vec![color])
-    }
 }
 
 impl Colored {
@@ -405,45 +318,6 @@ impl Colored {
         }
     }
 
-    /// returns the left and right halves as unique Colored instances. Keeps the original instance untouched.
-    pub fn Split (&self, midPoint: usize) -> (Colored, Colored) {
-        (
-            Colored {
-                text: self.text[..midPoint].to_string(),
-                mods: self.mods.clone(),
-                color: self.color.clone(),
-                bgColor: self.bgColor.clone(),
-            },
-            Colored {
-                text: self.text[midPoint..].to_string(),
-                mods: self.mods.clone(),
-                color: self.color.clone(),
-                bgColor: self.bgColor.clone(),
-            }
-        )
-    }
-
-    pub fn IsUncolored (&self) -> bool {
-        self.mods.is_empty() && self.color.is_none() && self.bgColor.is_none()
-    }
-
-    pub fn Contains (&self, color: &ColorType) -> bool {
-        let col = color.GetColor().UnwrapIntoTuple();
-        if col.0 == self.bgColor && col.2 {  return true;  }
-        if let Some(selfColor) = &self.color {
-            if let Some(otherCol) = col.0 {
-                if selfColor.contains(&otherCol) {  return true;  }
-            }
-        }
-        for modifier in col.1 {
-            if self.mods.contains(&modifier) {  return true;  }
-        } false
-    }
-
-    pub fn ChangeText (&mut self, text: String) {
-        self.text = text;
-    }
-
     // Adds a color type
     pub fn AddColor (&mut self, color: ColorType) {
         self.AddUnique(color.GetColor());
@@ -483,17 +357,8 @@ impl Colored {
         } colored
     }
 
-    // Takes a set of unique colors and generates a filled out instance
-    pub fn GetFromUniqueColors (text: String, uniqueColors: Vec <UniqueColor>) -> Colored {
-        let mut colored = Colored::new(text);
-        for color in uniqueColors {
-            colored.AddUnique(color);
-        } colored
-    }
-
-    pub fn GetText (&self, lastColor: &mut String) -> (String, usize) {
-        let mut text = String::new();
-
+    // the escape code for the colors/modifiers (starting with a reset, so it doesn't depend on what came before)
+    pub fn GetStyle (&self) -> String {
         let col = match &self.color {
             Some(colr) => colr,
             _ => &String::new()
@@ -504,7 +369,7 @@ impl Colored {
             _ => &String::new()
         };
 
-        let color = match
+        match
             (self.bgColor.is_some(), self.color.is_some(), !self.mods.is_empty())
         {
             (true, true, true) => format!("\x1b[0;{};{};{}m", col, bgCol, self.mods.join(";")),
@@ -514,20 +379,12 @@ impl Colored {
             (true, false, true) => format!("\x1b[0;{};{}m", bgCol, self.mods.join(";")),
             (true, false, false) => format!("\x1b[0;{}m", bgCol),
             (false, false, _) => String::from("\x1b[0m"),
-        };
-
-        if color != *lastColor {
-            //text.push_str(CLEAR);
-            text.push_str(&color);
-            *lastColor = color;
         }
-
-        text.push_str(&self.text);
-        (text, self.text.chars().count())
     }
 
+    // the width in terminal cells (not chars or bytes)
     pub fn GetSize (&self) -> usize {
-        self.text.chars().count()
+        GetDisplayWidth(&self.text)
     }
 }
 
@@ -552,17 +409,59 @@ impl Span {
         size
     }
 
-    pub fn Join (&self) -> (String, usize) {
-        //let mut lastColored = vec![];
-        let mut lastColored = String::new();
-        let mut total = String::new();
-        let mut totalSize = 0;
-        for colored in &self.line {
-            let (text, size) = colored.GetText(&mut lastColored);
-            total.push_str(&text);
-            totalSize += size;
+    // adds another span's tokens onto the end
+    pub fn Extend (&mut self, other: Span) {
+        self.line.extend(other.line);
+    }
+
+    // Breaks the span into lines no wider than the given width (in cells), keeping each token's colors
+    // Lines are broken at the last space that fits, or mid word if a single word is too long
+    pub fn Wrap (&self, width: usize) -> Vec <Span> {
+        let width = std::cmp::max(width, 1);
+        if self.Size() <= width {  return vec![self.clone()];  }
+
+        // every grapheme alongside the index of the token it came from
+        let graphemes: Vec <(&str, usize)> = self.line.iter().enumerate()
+            .flat_map(|(index, colored)| colored.text.graphemes(true).map(move |grapheme| (grapheme, index)))
+            .collect();
+        let mut lines = vec![];
+        let mut start = 0;
+        loop {
+            // the first grapheme that doesn't fit
+            let mut end = start;
+            let mut used = 0;
+            while end < graphemes.len() && used + GetDisplayWidth(graphemes[end].0) <= width {
+                used += GetDisplayWidth(graphemes[end].0);
+                end += 1;
+            }
+            if end == graphemes.len() {  break;  }
+            let (lineEnd, nextStart) = match (start + 1..=end).rev().find(|index| graphemes[*index].0 == " ") {
+                Some(space) => (space, space + 1),  // the space itself is dropped
+                None => (std::cmp::max(end, start + 1), std::cmp::max(end, start + 1)),
+            };
+            lines.push(self.SliceGraphemes(&graphemes[start..lineEnd]));
+            start = nextStart;
         }
-        (total, totalSize)
+        lines.push(self.SliceGraphemes(&graphemes[start..]));
+        lines
+    }
+
+    // Rebuilds a span from graphemes tagged with their token (see Wrap)
+    fn SliceGraphemes (&self, graphemes: &[(&str, usize)]) -> Span {
+        let mut tokens: Vec <Colored> = vec![];
+        let mut lastToken = usize::MAX;
+        for (grapheme, token) in graphemes {
+            if *token != lastToken {
+                let mut colored = self.line[*token].clone();
+                colored.text.clear();
+                tokens.push(colored);
+                lastToken = *token;
+            }
+            if let Some(colored) = tokens.last_mut() {
+                colored.text.push_str(grapheme);
+            }
+        }
+        Span::FromTokens(tokens)
     }
 }
 
@@ -578,19 +477,22 @@ pub struct Window {
     pub position: (u16, u16),
     pub depth: u16,
     pub size: (u16, u16),
-    updated: Vec <bool>,
+    // whether the window has been drawn since it last changed
     wasUpdated: bool,
 
-    // (Span, cached render, num visible chars)
-    lines: Vec <(Span, String, usize)>,
+    lines: Vec <Span>,
 
     bordered: bool,
     title: (Span, usize),
     color: Colored,
     pub hidden: bool,
-}
 
-type RenderClosure = Vec <(Box <dyn FnOnce () -> String + Send>, u16, u16, u16)>;
+    // how far the lines are scrolled (columns, lines); there can be more lines than fit in the window
+    scroll: (usize, usize),
+    // whether the mouse wheel scrolls the window (see App::ScrollAt), and whether the border shows where it's scrolled to
+    scrollable: bool,
+    scrollbar: bool,
+}
 
 impl Window {
     pub fn new (position: (u16, u16), depth: u16, size: (u16, u16)) -> Self {
@@ -598,30 +500,18 @@ impl Window {
             position,
             depth,
             size,
-            updated: vec![false; size.1 as usize],
             wasUpdated: false,
             lines: vec![],
             bordered: false,
             title: (Span::default(), 0),
             color: Colored::new(String::new()),  // format!("\x1b[38;2;{};{};{}m", 125, 125, 0),//String::new(),
             hidden: false,
+            scroll: (0, 0),
+            scrollable: false,
+            scrollbar: false,
         }
     }
 
-    pub fn Hide (&mut self) -> bool {
-        if self.hidden {  return false;  }
-        self.hidden = true;
-        self.UpdateAll();
-        true
-    }
-
-    pub fn Show(&mut self) -> bool {
-        if !self.hidden {  return false;  }
-        self.hidden = false;
-        self.UpdateAll();
-        true
-    }
-
     pub fn Move (&mut self, newPosition: (u16, u16)) {
         if newPosition == self.position {  return;  }
         self.position = newPosition;
@@ -634,51 +524,84 @@ impl Window {
         } self.UpdateAll();
     }
 
-    pub fn Colorize (&mut self, color: ColorType) {
-        self.color.AddColor(color);
-        self.UpdateAll();
+    // Adds a border around the window/block
+    pub fn Bordered (&mut self) {
+        self.bordered = true;
     }
 
-    pub fn TryColorize (&mut self, color: ColorType) -> bool {
-        if self.color.Contains(&color) {  return false;  }
-        self.color.AddColor(color);
+    // Scrollable, with scrollbars drawn in the border whenever the lines don't all fit
+    // (along the right side for going up and down, and along the bottom for going sideways)
+    pub fn Scrollbar (&mut self) {
+        self.scrollable = true;
+        self.scrollbar = true;
         self.UpdateAll();
-        true
     }
 
-    pub fn ClearColors (&mut self) -> bool {
-        if self.color.IsUncolored() { return false; }
-        self.color = Colored::new(String::new());
+    // the space inside the border (columns, lines)
+    pub fn GetViewportSize (&self) -> (usize, usize) {
+        let border = self.bordered as usize * 2;
+        ((self.size.0 as usize).saturating_sub(border), (self.size.1 as usize).saturating_sub(border))
+    }
+
+    // the size of everything in the window (columns, lines), which can be bigger than the viewport
+    pub fn GetContentSize (&self) -> (usize, usize) {
+        (self.lines.iter().map(|line| line.Size()).max().unwrap_or(0), self.lines.len())
+    }
+
+    // how far the window is scrolled (columns, lines)
+    pub fn GetScroll (&self) -> (usize, usize) {
+        self.scroll
+    }
+
+    // Scrolls so the column and line are at the top left, as far as the lines allow
+    // Returns whether it moved
+    pub fn ScrollTo (&mut self, scroll: (usize, usize)) -> bool {
+        let content = self.GetContentSize();
+        let viewport = self.GetViewportSize();
+        let scroll = (
+            std::cmp::min(scroll.0, content.0.saturating_sub(viewport.0)),
+            std::cmp::min(scroll.1, content.1.saturating_sub(viewport.1)),
+        );
+        if scroll == self.scroll {  return false;  }
+        self.scroll = scroll;
         self.UpdateAll();
         true
     }
 
-    // Adds a border around the window/block
-    pub fn Bordered (&mut self) {
-        self.bordered = true;
+    // scrolls by a number of (columns, lines), negative being left/up
+    pub fn ScrollBy (&mut self, amount: (i32, i32)) -> bool {
+        self.ScrollTo((
+            self.scroll.0.saturating_add_signed(amount.0 as isize),
+            self.scroll.1.saturating_add_signed(amount.1 as isize),
+        ))
     }
 
-    // Sets/updates the title of the window/block
-    pub fn Titled (&mut self, title: String) {
-        self.title = (
-            Span::FromTokens(
-            vec![title.Colorizes(vec![])]),
-            title.chars().count()
-        );
-        self.wasUpdated = false;
-        self.updated[0] = false;
-        //self.color.ChangeText(title);
+    // keeps the scroll within the lines after they've changed
+    fn ClampScroll (&mut self) {
+        self.ScrollTo(self.scroll);
     }
 
-    pub fn HasTitle (&self) -> bool {
-        self.title.1 != 0
+    // the row (within the window, border included) a line is drawn on, if it's scrolled into view
+    fn GetLineRow (&self, line: usize) -> Option <usize> {
+        let row = line.checked_sub(self.scroll.1)?;
+        if row >= self.GetViewportSize().1 {  return None;  }
+        Some(row + self.bordered as usize)
+    }
+
+    // Where the thumb goes along a scrollbar of the given length, if the content doesn't all fit
+    // The thumb's size shows how much is in view, and it only reaches the end once scrolled all the way
+    fn GetScrollbarThumb (content: usize, viewport: usize, scroll: usize, track: usize) -> Option <std::ops::Range <usize>> {
+        if content <= viewport || track == 0 {  return None;  }
+        let size = (track * viewport / content).clamp(1, track);
+        let start = scroll * (track - size) / (content - viewport);
+        Some(start..start + size)
     }
 
     pub fn TitledColored (&mut self, title: Span) {
+        if self.title.0 == title {  return;  }  // so setting it every frame doesn't redraw the border
         let size = title.Size();
         self.title = (title, size);
         self.wasUpdated = false;
-        self.updated[0] = false;
     }
 
     pub fn Resize (&mut self, changed: (u16, u16)) -> bool {
@@ -687,320 +610,124 @@ impl Window {
             std::cmp::max(changed.0, 0),
             std::cmp::max(changed.1, 0)
         );
-        self.updated = vec![false; self.size.1 as usize];
         self.UpdateAll();
+        self.ClampScroll();
         true
     }
     
-    // Clamps a string to a maximum length of visible UTF-8 characters while preserving escape codes
-    fn ClampStringVisibleUTF_8 (text: &str, maxLength: usize) -> String {
-        let mut accumulative: String = String::new();
-
-        let mut visible = 0;
-        let mut inEscape = false;
-        for chr in text.chars() {
-            if chr == '\x1b' {
-                inEscape = true;
-            } else if inEscape {
-                if chr == 'm' {
-                    inEscape = false;
-                }
-            } else {
-                visible += 1;
-                if visible > maxLength {  break;  }
-            }
-            accumulative.push(chr);
-        }
-
-        accumulative
-    }
-
-    pub fn RenderWindowSlice (color: (String, usize),
-                              bordered: bool,
-                              renderText: (String, usize),
-                              size: (u16, u16)
-    ) -> String {
-        let mut text = String::new();
-
-        //let line = &self.lines[index - 1];//self.lines[0..self.size.1 as usize - borderSize][0];
-        let borderSize = match bordered {
-            true => 2, false => 0
-        };
-        let lineText = Window::ClampStringVisibleUTF_8(
-            &renderText.0, size.0 as usize - borderSize
-        );
-        let lineSize = std::cmp::min(renderText.1, size.0 as usize - borderSize);
-
-        // handling the side borders
-        if bordered {
-            text.push_str(&color.0);
-            text.push('│');
-            text.push_str(CLEAR);
-            text.push_str(&lineText);
-            text.push_str(CLEAR);
-            let padding = (size.0 as usize - 2) - lineSize;
-            text.push_str(&" ".repeat(padding));
-            text.push_str(&color.0);
-            text.push('│');
-            text.push_str(CLEAR);
-        } else {
-            text.push_str(&lineText);
-            text.push_str(CLEAR);  // making sure the following are blank
-            let padding = (size.0 as usize) - lineSize;
-            text.push_str(&" ".repeat(padding));
-        } text
-    }
-
-    fn HandleHiddenClosure (&mut self, mut renderClosures: RenderClosure) -> RenderClosure {
-        self.wasUpdated = true;
-        for i in 0..self.updated.len() {
-            if self.updated[i] {  continue;  }
-            self.updated[i] = true;
-            let width = self.size.0;
-            renderClosures.push((Box::new(move || {
-                " ".repeat(width as usize)
-            }), self.position.0, self.position.1 + i as u16, 0));  // the depth is 0, right?
-        }
-        renderClosures
-    }
-
-    pub fn GetRenderClosure (&mut self) -> RenderClosure {
-        if self.wasUpdated {  return vec![];  }  // no re-rendering is needed
-
-        let mut renderClosures: RenderClosure = vec![];
-        if self.hidden {
-            return self.HandleHiddenClosure(renderClosures);
-        }
-
-        // these will need to be sorted by row, and the cursor movement is handled externally (the u16 pair)
-        let borderColor = self.color.GetText(&mut String::new());
+    // Draws the window into the grid (see App::Render), border and all
+    // Rows past the last line, and the space after each line, are left blank
+    pub fn Draw (&mut self, grid: &mut grid::Grid) {
         self.wasUpdated = true;
-
-        // make sure to not call UpdateRender when using closures
-        let borderedSize = {
-            if self.bordered {  1  }
-            else {  0  }
+        if self.hidden || (self.bordered && (self.size.0 < 2 || self.size.1 < 2)) {  return;  }
+
+        let borderStyle: grid::CellStyle = self.color.GetStyle().into();
+        let clear = grid.GetClearStyle();
+        let (left, top) = (self.position.0.saturating_sub(1) as usize, self.position.1.saturating_sub(1) as usize);
+        let (width, height) = (self.size.0 as usize, self.size.1 as usize);
+        let border = self.bordered as usize;
+        let content = self.GetContentSize();
+        let viewport = self.GetViewportSize();
+        let (verticalThumb, horizontalThumb) = match self.scrollbar && self.bordered {
+            true => (
+                Window::GetScrollbarThumb(content.1, viewport.1, self.scroll.1, viewport.1),
+                Window::GetScrollbarThumb(content.0, viewport.0, self.scroll.0, viewport.0),
+            ),
+            false => (None, None),
         };
-        let mut updated = false;
-        for index in borderedSize..self.size.1 as usize - borderedSize {
-            if self.updated[index] {  continue;  }
-            self.updated[index] = true;
-            updated = true;
-
-            let (text, size);
-            if index - borderedSize < self.lines.len() {
-                (text, size) = self.lines[index - borderedSize].0.Join();
-                self.lines[index - borderedSize].1 = text.clone();
-                self.lines[index - borderedSize].2 = size;
-            } else {
-                (text, size) = (String::new(), 0);
-            }
-
-            // creating the closure
-            let color = borderColor.clone();
-            let windowSize = self.size;  // idk a better way to do this other than cloning
-            let bordered = self.bordered;
 
-            let closure = move || {
-                Window::RenderWindowSlice(color, bordered, (text, size), windowSize)
+        for row in 0..viewport.1 {
+            let y = top + border + row;
+            let used = match self.lines.get(row + self.scroll.1) {
+                Some(line) => grid.DrawSpan(left + border, y, line, self.scroll.0, viewport.0),
+                None => 0,
             };
-            renderClosures.push((Box::new(closure), self.position.0, self.position.1 + index as u16, self.depth + 1));
-        }
-
-        if updated && self.bordered {
-            self.updated[self.size.1 as usize - 1] = true;
-            self.updated[0] = true;
-
-            // adding the top and bottom lines to the closures
-            let color = borderColor.clone();
-            let windowSize = self.size.0;  // idk a better way to do this other than cloning
-            let closure = move || {  // top
-                let mut text = String::new();
-                text.push_str(&color.0);
-                text.push('└');
-                text.push_str(&"─".repeat(windowSize as usize - 2));
-                text.push('┘');
-                text.push_str(CLEAR);
-                text
-            };
-            renderClosures.push((Box::new(closure), self.position.0, self.position.1 + self.size.1 - 1, self.depth + 1));
-
-            // bottom
-            let color = borderColor;  // consuming border color here
-            let windowSize = self.size.0;  // idk a better way to do this other than cloning
-            let title = self.title.clone();
-            let closure = move || {
-                let mut text = String::new();
-                text.push_str(&color.0);
-                text.push('┌');
-                let half = windowSize / 2 - title.1 as u16 / 2 - 1;
-                text.push_str(&"─".repeat(half as usize));
-                text.push_str(CLEAR);
-                text.push_str(&title.0.Join().0);
-                text.push_str(&color.0);
-                text.push_str(&"─".repeat(windowSize as usize - 2 - half as usize - title.1));
-                text.push('┐');
-                text.push_str(CLEAR);
-                text
-            };
-            renderClosures.push((Box::new(closure), self.position.0, self.position.1, self.depth + 1));
-        }
-
-        renderClosures
-    }
-
-    // Gets the rendered text for the individual window
-    // This shouldn't crash when rendering out of bounds unlike certain other libraries...
-    pub fn GetRender (&self) -> Vec <String> {
-        let mut text = vec![String::new()];
-        let color = self.color.GetText(&mut String::new());
-
-        // handling the top border
-        let borderSize =
+            grid.Fill(left + border + used, y, viewport.0 - used, " ", &clear);
             if self.bordered {
-                let mut lineSize = 1;
-                text[0].push_str(&color.0);
-                text[0].push('┌');
-                let splitSize = (self.size.0 - 2) / 2 - self.title.1 as u16 / 2;
-                lineSize += splitSize;
-                text[0].push_str(&"─".repeat(splitSize as usize));
-                lineSize += self.title.1 as u16;
-                text[0].push_str(&self.title.0.Join().0);
-                //let lineSize = text[0].len();
-                text[0].push_str(&"─".repeat(
-                    (self.size.0 as usize).saturating_sub(1 + lineSize as usize)
-                ));
-                text[0].push('┐');
-                text[0].push_str(CLEAR);
-                //text[0].push('\n');  // fix this
-                text.push(String::new());
-                2
-            }
-            else {  0  };
-        let bordered = borderSize / 2;
-        for index in bordered..self.size.1 as usize - bordered {
-            let lineText;
-            let lineSize;
-            if index <= self.lines.len() {
-                let line = &self.lines[index - 1];//self.lines[0..self.size.1 as usize - borderSize][0];
-                lineText = Window::ClampStringVisibleUTF_8(
-                    &line.1, self.size.0 as usize - borderSize
-                );
-                lineSize = std::cmp::min(self.lines[index - 1].2, self.size.0 as usize - borderSize);
-            } else {
-                lineText = String::new();
-                lineSize = 0;
+                let rightBorder = match verticalThumb.as_ref().is_some_and(|thumb| thumb.contains(&row)) {
+                    true => "┃",
+                    false => "│",
+                };
+                grid.Set(left, y, "│", &borderStyle);
+                grid.Set(left + width - 1, y, rightBorder, &borderStyle);
             }
-
-            // handling the side borders
-            if self.bordered {
-                text[index].push_str(&color.0);
-                text[index].push('│');
-                text[index].push_str(CLEAR);
-                text[index].push_str(&lineText);
-                text[index].push_str(CLEAR);
-                let padding = (self.size.0 as usize - 2) - lineSize;
-                text[index].push_str(&" ".repeat(padding));
-                text[index].push_str(&color.0);
-                text[index].push('│');
-                text[index].push_str(CLEAR);
-            } else {
-                text[index].push_str(&lineText);
-                let padding = (self.size.0 as usize) - lineSize;
-                text[index].push_str(&" ".repeat(padding));
-            }
-            text.push(String::new());
         }
-
-        // handling the bottom border
-        let lastIndex = text.len() - 1;
-        if self.bordered {
-            text[lastIndex].push_str(&color.0);
-            text[lastIndex].push('└');
-            text[lastIndex].push_str(&"─".repeat(self.size.0 as usize - 2));
-            text[lastIndex].push('┘');
-            text[lastIndex].push_str(CLEAR);
-        } else {
-            // removing the last \n
-            text.pop();
+        if !self.bordered {  return;  }
+
+        // the top, with the title in the middle
+        let half = (width / 2).saturating_sub(self.title.1 / 2 + 1);
+        grid.Set(left, top, "┌", &borderStyle);
+        grid.Fill(left + 1, top, half, "─", &borderStyle);
+        let titleSize = grid.DrawSpan(left + 1 + half, top, &self.title.0, 0, (width - 2).saturating_sub(half));
+        grid.Fill(left + 1 + half + titleSize, top, (width - 2).saturating_sub(half + titleSize), "─", &borderStyle);
+        grid.Set(left + width - 1, top, "┐", &borderStyle);
+
+        // the bottom, which doubles as the sideways scrollbar
+        let bottom = top + height - 1;
+        grid.Set(left, bottom, "└", &borderStyle);
+        for column in 0..width - 2 {
+            let edge = match horizontalThumb.as_ref().is_some_and(|thumb| thumb.contains(&column)) {
+                true => "━",
+                false => "─",
+            };
+            grid.Set(left + 1 + column, bottom, edge, &borderStyle);
         }
-        text
-    }
-
-    // Replaces a single line with an updated version
-    pub fn UpdateLine (&mut self, index: usize, span: Span) {
-        if index >= self.lines.len() {  return;  }
-        self.lines[index] = (span, String::new(), 0);
-        self.updated[index] = false;
-        self.wasUpdated = false;
+        grid.Set(left + width - 1, bottom, "┘", &borderStyle);
     }
 
     // Appends a single line to the window
     pub fn AddLine (&mut self, span: Span) {
-        self.lines.push((span, String::new(), 0));
-        self.updated.push(false);
-        self.wasUpdated = false;
+        self.lines.push(span);
+        self.MarkLine(self.lines.len() - 1);
+    }
+
+    // redraws the window if the line is in view
+    fn MarkLine (&mut self, line: usize) {
+        if self.GetLineRow(line).is_some() {
+            self.wasUpdated = false;
+        }
     }
 
     // Takes a vector of type Span
     // That Span replaces the current set of lines for the window
+    // There can be more lines than fit, with the rest scrolled to
     pub fn FromLines (&mut self, lines: Vec <Span>) {
-        self.lines.clear();// self.updated.clear();
-        let mut index = {
-            if self.bordered {  1  }
-            else {  0  }
-        };
-        for span in lines {
-            self.lines.push((span, String::new(), 0));
-            self.updated[index] = false;
-            self.wasUpdated = false;
-            index += 1;
-        }
+        self.lines = lines;
+        self.UpdateAll();
+        self.ClampScroll();
     }
 
     // checks to see if any lines need to be updated
     pub fn TryUpdateLines (&mut self, mut lines: Vec <Span>) {
         if lines.len() != self.lines.len() {
             self.UpdateAll();  // making sure every line gets updated (incase it was shrunk)
-            self.wasUpdated = false;
-            self.lines.clear();
-            for (index, span) in lines.into_iter().enumerate() {
-                if index >= self.updated.len() {  break;  }
-                self.lines.push((span, String::new(), 0));
-            }
+            self.lines = lines;
+            self.ClampScroll();
             return;
         }
         let mut index = lines.len();
-        let bordered = {
-            if self.bordered {  1  }
-            else {  0  }
-        };
         while let Some(span) = lines.pop() {
             index -= 1;  // the pop already subtracted one
-            if self.lines[index].0 != span {
-                self.lines[index] = (span, String::new(), 0);
-                self.updated[index + bordered] = false;  // it was as easy as adding a plus 1....... me sad
-                self.wasUpdated = false;
+            if self.lines[index] != span {
+                self.lines[index] = span;
+                self.MarkLine(index);
             }
         }
     }
 
-    pub fn IsEmpty (&self) -> bool {
-        self.lines.is_empty()
+    // whether anything in the window still has to be drawn
+    pub fn NeedsRender (&self) -> bool {
+        !self.wasUpdated
     }
 
-    pub fn UpdateAll (&mut self) {
-        for line in self.updated.iter_mut() {
-            *line = false;
-        }
-        self.wasUpdated = false;
+    // whether a position on the screen is within the window (border included)
+    pub fn Contains (&self, position: (u16, u16)) -> bool {
+        position.0 >= self.position.0 && position.0 < self.position.0 + self.size.0 &&
+        position.1 >= self.position.1 && position.1 < self.position.1 + self.size.1
     }
 
-    pub fn SupressUpdates (&mut self) {
-        for line in self.updated.iter_mut() {
-            *line = true;
-        }
-        self.wasUpdated = true;
+    pub fn UpdateAll (&mut self) {
+        self.wasUpdated = false;
     }
 }
 
@@ -1012,50 +739,125 @@ pub struct Rect {
     pub height: u16,
 }
 
+// works out where a window goes from the size of the terminal (see App::PlaceWindow)
+pub type Placement = Box <dyn Fn (&Rect) -> layout::Area>;
+
+// what the mouse did over a window
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum MouseAction {
+    // the left button went down
+    Press,
+    // moved while the left button is held
+    Drag,
+    // the left button was let go of
+    Click,
+    RightClick,
+    // moved without any button held
+    Hover,
+    // how many lines were scrolled (negative is up)
+    Scroll (i32),
+}
+
+// the window under the mouse, and where in it the mouse is
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MouseHit {
+    pub window: String,
+    // the position on the screen
+    pub position: (u16, u16),
+    // the line and column within the window's text (inside the border, if there is one)
+    // these count from the start of the text, so take any scrolling into account
+    pub line: usize,
+    pub column: usize,
+    // the border isn't part of the text, so hits on it are clamped to the nearest line/column
+    pub onBorder: bool,
+}
+
+// called with whatever the mouse did over the window it was registered for
+pub type MouseHandler <T> = fn (&mut T, &MouseHit, MouseAction);
+
+// Sends mouse events to the handler of the window they happened over
+// Only the topmost window gets the event, so anything a window covers can't be clicked through it
+pub struct MouseRouter <T> {
+    handlers: std::collections::HashMap <String, MouseHandler <T>>,
+}
+
+impl <T> Default for MouseRouter <T> {
+    fn default () -> Self {
+        MouseRouter {
+            handlers: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl <T> MouseRouter <T> {
+    // Sets the handler for a window (by name), replacing any it had
+    // The window doesn't need to exist yet; it just won't get anything until it does
+    pub fn On (&mut self, window: String, handler: MouseHandler <T>) {
+        self.handlers.insert(window, handler);
+    }
+
+    // The handler for the window under the position, along with where in the window it is
+    // The handler is handed back rather than called so the app can pass itself in
+    pub fn Route (&self, app: &App, position: (u16, u16)) -> Option <(MouseHandler <T>, MouseHit)> {
+        let hit = app.GetWindowAt(position)?;
+        let handler = *self.handlers.get(&hit.window)?;
+        Some((handler, hit))
+    }
+}
+
+// the placements of windows by name (only wrapped so App can still be debug printed)
+#[derive(Default)]
+struct Placements (std::collections::HashMap <String, Placement>);
+
+impl std::fmt::Debug for Placements {
+    fn fmt (&self, f: &mut std::fmt::Formatter <'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
 // the main application. It stores and handles the active windows
 // It also handles rendering the cumulative sum of the windows
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct App {
     area: Rect,
     activeWindows: Vec <(Window, Vec <String>)>,  // window, mods
     windowReferences: std::collections::HashMap <String, usize>,
+    placements: Placements,
     changeWindowLayout: bool,
     updated: bool,
-    renderHandle: Option <std::thread::JoinHandle <()>>,
-    buffer: std::sync::Arc <parking_lot::RwLock <String>>,
     resetWindows: bool,
+    // where the frames are written (normally the terminal)
+    backend: Box <dyn backend::Backend>,
+    // the last frame handed to the backend, which each frame is compared against
+    screen: Option <std::sync::Arc <grid::Grid>>,
+    metrics: backend::RenderMetrics,
 }
 
 impl Drop for App {
     fn drop (&mut self) {
-        print!("{SHOW_CURSOR}");  // showing the cursor
-
-        // clearing the screen
-        //print!("\x1B[2J\x1B[H\x1b");
-        print!("\x1B[0m");
-        print!("\x1B[?1049l");
-        print!("\x1B[2K\x1B[E");
-        print!("\x1Bc");
-
-        // I don't really care if an error is thrown at this point
-        let _ = std::io::stdout().flush();
+        self.backend.Leave();
     }
 }
 
 impl App {
-    pub fn new () -> Self {  // 1049h
-        print!("\x1B7");
-        print!("\x1B[?1049h");
-        print!("\x1B[?25l");
+    pub fn new () -> Self {
+        App::WithBackend(Box::new(backend::TerminalBackend::default()))
+    }
+
+    // an app rendering somewhere other than the terminal (like backend::HeadlessBackend for tests)
+    pub fn WithBackend (mut backend: Box <dyn backend::Backend>) -> Self {
+        backend.Enter();
         App {
             area: Rect::default(),
             activeWindows: vec![],
             windowReferences: std::collections::HashMap::new(),
             changeWindowLayout: true,
             updated: true,
-            renderHandle: None,
-            buffer: std::sync::Arc::new(parking_lot::RwLock::new(String::new())),
             resetWindows: false,
+            backend,
+            placements: Placements::default(),
+            screen: None,
+            metrics: backend::RenderMetrics::default(),
         }
     }
 
@@ -1063,28 +865,13 @@ impl App {
         self.windowReferences.contains_key(&name)
     }
 
-    pub fn GetWindowReference (&self, name: String) -> &Window {
-        &self.activeWindows[self.windowReferences[&name]].0
-    }
-
     pub fn GetWindowReferenceMut (&mut self, name: String) -> &mut Window {
         //self.updated = true;  // assuming something is being changed
         &mut self.activeWindows[self.windowReferences[&name]].0
     }
 
-    pub fn UpdateWindowLayoutOrder (&mut self) {
-        self.changeWindowLayout = true;
-        //self.updated = true;
-    }
-
     pub fn GetTerminalSize (&self) -> Result <(u16, u16), std::io::Error> {
-        crossterm::terminal::size()
-    }
-
-    // Gets the current window size and position
-    // This returns a reference to this instance
-    pub fn GetWindowArea (&self) -> &Rect {
-        &self.area
+        self.backend.GetSize()
     }
 
     // Adds a new active window
@@ -1092,15 +879,82 @@ impl App {
         if !window.hidden {  self.changeWindowLayout = true;  }  // if the window is hidden, it shouldn't change anything
         self.windowReferences.insert(name, self.windowReferences.len());
         self.activeWindows.push((window, keywords));
-        //self.updated = true;
+        self.updated = true;
+    }
+
+    // Keeps a window wherever the placement puts it, moving and resizing it whenever the terminal is
+    // resized. The window can be added before or after, and removing it forgets the placement
+    pub fn PlaceWindow (&mut self, name: String, placement: impl Fn (&Rect) -> layout::Area + 'static) {
+        if let Some(index) = self.windowReferences.get(&name) && self.area != Rect::default() {
+            let area = placement(&self.area);
+            let window = &mut self.activeWindows[*index].0;
+            window.Move(area.position);
+            window.Resize(area.size);
+        }
+        self.placements.0.insert(name, Box::new(placement));
+    }
+
+    fn ApplyPlacements (&mut self) {
+        for (name, placement) in &self.placements.0 {
+            let Some(index) = self.windowReferences.get(name) else {  continue;  };
+            let area = placement(&self.area);
+            let window = &mut self.activeWindows[*index].0;
+            window.Move(area.position);
+            window.Resize(area.size);
+        }
+    }
+
+    // Sets the size of the terminal, re-placing the placed windows if it changed
+    // Rendering does this too, but doing it first means the windows are already their new size when filled in
+    pub fn UpdateArea (&mut self, size: (u16, u16)) -> bool {
+        if size == (self.area.width, self.area.height) {  return false;  }
+        self.area = Rect {
+            width: size.0,
+            height: size.1,
+        };
+        self.resetWindows = true;  // everything has to be redrawn
+        self.ApplyPlacements();
+        true
+    }
+
+    // Finds the topmost visible window at a position on the screen
+    // Windows are drawn by depth, and ones at the same depth in the order they were added, so the last drawn wins
+    pub fn GetWindowAt (&self, position: (u16, u16)) -> Option <MouseHit> {
+        let (name, index) = self.windowReferences.iter()
+            .filter(|(_, index)| {
+                let window = &self.activeWindows[**index].0;
+                !window.hidden && window.Contains(position)
+            })
+            .max_by_key(|(_, index)| (self.activeWindows[**index].0.depth, **index))?;
+        let window = &self.activeWindows[*index].0;
+        let line = position.1 - window.position.1;
+        let column = position.0 - window.position.0;
+        let border = window.bordered as u16;
+        Some(MouseHit {
+            window: name.clone(),
+            position,
+            line: line.saturating_sub(border).min(window.size.1.saturating_sub(border * 2 + 1)) as usize + window.scroll.1,
+            column: column.saturating_sub(border).min(window.size.0.saturating_sub(border * 2 + 1)) as usize + window.scroll.0,
+            onBorder: window.bordered && (line == 0 || column == 0 || line + 1 == window.size.1 || column + 1 == window.size.0),
+        })
+    }
+
+    // Scrolls the topmost window under a position by (columns, lines), if it's scrollable
+    // Returns whether a window took the scroll, so the app can handle it for any that aren't
+    pub fn ScrollAt (&mut self, position: (u16, u16), amount: (i32, i32)) -> bool {
+        let Some(hit) = self.GetWindowAt(position) else {  return false;  };
+        let window = self.GetWindowReferenceMut(hit.window);
+        if !window.scrollable {  return false;  }
+        window.ScrollBy(amount);
+        true
     }
 
     // Pops an active window.
     // Returns Ok(window) if the index is valid, or Err if out of bounds
     pub fn RemoveWindow (&mut self, name: String) -> Result <Window, String> {
         self.changeWindowLayout = true;
-        self.resetWindows = true;
-        //self.updated = true;
+        self.placements.0.remove(&name);
+        self.updated = true;  // whatever was under it gets drawn again
 
         if !self.windowReferences.contains_key(&name) {
             return Err(format!("No window named '{}' found", name));
@@ -1130,141 +984,68 @@ impl App {
         Ok(self.activeWindows.remove(index).0)
     }
 
-    /// Gathers the specified range of the string while accounting for non-visible
-    /// UTF-8 character escape codes. Instead of each byte being a character, the characters
-    /// are determined based on character boundaries and escape code sequences.
-    pub fn GetSliceUTF_8 (text: &str, range: std::ops::Range <usize>) -> String
-    where
-        std::ops::Range<usize>: Iterator<Item = usize>
-    {
-        let mut visible = 0;
-        let mut inEscape = false;
-        let mut slice = String::new();
-        for chr in text.chars() {
-            if chr == '\x1b' {
-                inEscape = true;
-
-                // making sure to keep the initial escape codes
-                slice.push(chr);
-            } else if inEscape {
-                inEscape = chr != 'm';
-
-                // making sure to keep the initial escape codes
-                slice.push(chr);
-            } else {
-                visible += 1;
-                if visible >= range.start {
-                    if visible < range.end {
-                        // adding the element to the slice
-                        slice.push(chr);
-                        continue;
-                    }
-                    return slice;  // no need to continue
-                }
-            }
-        } slice
-    }
-
-    fn HandleRenderWindowChanges (&mut self, size: &(u16, u16)) {
-        let handle = self.renderHandle.take();
-        if let Some(handle) = handle {
-            // if an error was thrown, I don't care anymore
-            let _ = handle.join();
-        }
-
-        self.buffer.write().clear();
-        if size.0 != self.area.width || size.1 != self.area.height || self.resetWindows {
-            self.resetWindows = false;
-            *self.buffer.write() = String::with_capacity((size.0 * size.1 * 3) as usize);
-
-            // making sure the windows get updated
-            //self.updated = true;
-            for window in &mut self.activeWindows {
-                if window.0.hidden {  continue;  }  // hidden windows don't need re-rendering
-                window.0.UpdateAll();
-            }
-
-            // replace with an actual clear..... this doesn't work (it just shifts the screen--or does it???)
-            print!("\x1b[2J\x1b[H");  // re-clearing the screen (everything will need to update)
-        }
+    // Whether the next frame starts over from a cleared screen, which it does if the terminal was
+    // resized (what's left on it afterwards can't be relied on)
+    fn HandleRenderWindowChanges (&mut self, size: &(u16, u16)) -> bool {
+        let resized = self.screen.as_ref().is_none_or(|screen| screen.GetSize() != *size);
+        if !resized && !self.resetWindows {  return false;  }
+        self.resetWindows = false;
+        true
     }
 
-    // Renders all the active windows to the consol
-    // It also clears the screen from previous writing
-    pub fn Render (&mut self, terminalSize: Option <(u16, u16)>) -> usize {
+    // Renders all the active windows to the console
+    // The windows are drawn by depth into a grid of cells, which the backend compares against what's
+    // already on the screen so only the cells that differ are written. Returns whether a frame was sent
+    pub fn Render (&mut self, terminalSize: Option <(u16, u16)>) -> bool {
         // incase the size is needed and thus calculated elsewhere (to prevent recalculation which is slow)
         // (aka I'm too lazy to update the code I already made.....)
         let size = terminalSize.unwrap_or(self.GetTerminalSize().unwrap());
-        self.HandleRenderWindowChanges(&size);
-
-        self.area = Rect {
-            width: size.0,
-            height: size.1,
-        };
+        self.UpdateArea(size);
+        let cleared = self.HandleRenderWindowChanges(&size);
 
         // only re-rendering on updates (otherwise the current results are perfectly fine)
         // this should reduce CPU usage by a fair bit and allow a fast refresh rate if needed
-        let mut updated = false;
-        for window in &self.activeWindows {
-            if window.0.wasUpdated {  continue;  }
-            updated = true;
-            break;
-        }
-        if !updated {  return 0;  }
-        
-        // stores the draw calls
-        let mut drawCalls = vec![];
-
-        // going through the sorted windows
-        for window in &mut self.activeWindows {
-            //let window = &mut self.activeWindows[*index];
-            drawCalls.append(&mut window.0.GetRenderClosure());
+        let changed = self.activeWindows.iter().any(|window| window.0.NeedsRender());
+        if !changed && !cleared && !self.updated {  return false;  }
+        self.updated = false;
+
+        // drawn bottom up, with windows at the same depth in the order they were added (like GetWindowAt)
+        let start = std::time::Instant::now();
+        let mut frame = grid::Grid::new(size);
+        let mut order: Vec <usize> = (0..self.activeWindows.len()).collect();
+        order.sort_by_key(|index| self.activeWindows[*index].0.depth);
+        for index in order {
+            self.activeWindows[index].0.Draw(&mut frame);
         }
 
-        let numCalls = drawCalls.len();
-
-        let size = (self.area.width, self.area.height);
-        let buffer = self.buffer.clone();
-        //println!("Num calls: {}", drawCalls.len());
-        self.renderHandle = Some(std::thread::spawn(move || {
-            // the buffer for the render string
-
-            // sorting the calls by action row (and left to right for same row calls)
-            // drawCall.3 is the depth; higher numbers will be rendered last thus being on top (each depth is a unique layer)
-            drawCalls.sort_by_key(|drawCall| drawCall.2 * size.0 + drawCall.1 + drawCall.3 * size.0 * size.1);
-
-            // iterating through the calls (consuming drawCalls)
-            let writeBuffer = &mut *buffer.write();
-            for call in drawCalls {
-                // moving the cursor into position
-                // ESC[{line};{column}H
-                writeBuffer.push_str("\x1b[");
-                App::PushU16(writeBuffer, call.2);
-                writeBuffer.push(';');
-                App::PushU16(writeBuffer, call.1);
-                writeBuffer.push('H');
-
-                let output = call.0();
-                writeBuffer.push_str(&output);
-            }
+        self.metrics.drawTime = start.elapsed();
+        if !cleared && self.screen.as_deref() == Some(&frame) {  return false;  }
 
-            // moving the cursor to the bottom right
-            writeBuffer.push_str("\x1b[");
-            App::PushU16(writeBuffer, size.1);
-            writeBuffer.push(';');
-            App::PushU16(writeBuffer, size.0);
-            writeBuffer.push_str("H ");
+        let frame = std::sync::Arc::new(frame);
+        self.screen = Some(frame.clone());
+        self.metrics.framesDrawn += 1;
+        self.backend.Present(frame, cleared);
+        true
+    }
 
-            // rendering the buffer
-            let mut out = std::io::stdout().lock();
-            out.write_all(writeBuffer.as_bytes()).unwrap();
-            out.flush().unwrap();
-        }));
+    // what's on the screen as of the last render (the backend may still be writing it)
+    #[cfg(test)]
+    pub fn GetScreen (&self) -> Option <&grid::Grid> {
+        self.screen.as_deref()
+    }
 
-        numCalls
+    // the most frames written to the terminal per second (0 for no limit)
+    pub fn SetMaxFrameRate (&mut self, frameRate: u32) {
+        self.backend.SetMaxFrameRate(frameRate);
+    }
 
-        //let elapsed = start.elapsed();
-        //panic!("Render thread completed in {:?}", elapsed);
+    // how rendering has gone so far, for debugging
+    pub fn GetMetrics (&self) -> backend::RenderMetrics {
+        backend::RenderMetrics {
+            framesDrawn: self.metrics.framesDrawn,
+            drawTime: self.metrics.drawTime,
+            ..self.backend.GetMetrics()
+        }
     }
 
     /// Takes an u16 value and pushes the text form of it in an efficient manner.
@@ -1293,119 +1074,5 @@ impl App {
             names.push(name);
         } names
     }
-
-    /// Prunes all windows which contain one of the specified keywords.
-    /// Returns the number of windows pruned.
-    pub fn PruneByKeywords (&mut self, keywords: Vec <String>) -> usize {
-        let mut pruned = vec![];
-        for (index, window) in self.activeWindows.iter().enumerate() {
-            for word in &window.1 {
-                if keywords.contains(word) {
-                    //println!("\n:{:?}::", (index, word));
-                    pruned.push(index);
-                    break;
-                }
-            }
-        }
-        if pruned.is_empty() {  return 0;  }
-        self.changeWindowLayout = true;
-        self.resetWindows = true;
-        self.updated = true;
-
-        let mut numPruned = 0;
-        // pruned should be in ascending order
-        for index in &pruned {
-            self.PruneUpdate(*index, &mut numPruned);
-        } numPruned
-    }
-
-    fn PruneUpdate (&mut self, index: usize, numPruned: &mut usize) {
-        // shifting all the indexes
-        let mut toRemove = vec![];
-        for pair in self.windowReferences.iter_mut() {
-            if *pair.1 == index-*numPruned {
-                toRemove.push(pair.0.clone());
-                continue;
-            }
-            if *pair.1 >= index-*numPruned {  *pair.1 -= 1  }
-        }
-        for key in toRemove {
-            self.windowReferences.remove(&key);
-        }
-        let _ = self.activeWindows.remove(index-*numPruned);
-        *numPruned += 1;
-    }
-
-    /// Prunes all windows based on a given key (closure).
-    /// Returns the number of windows pruned.
-    /// If the closure returns true, the element is pruned. If it returns false it's kept.
-    pub fn PruneByKey (&mut self, key: Box <dyn Fn (&Vec <String>) -> bool>) -> usize {
-        let mut pruned = vec![];
-        for (index, window) in self.activeWindows.iter().enumerate() {
-            if key(&window.1) {
-                pruned.push(index);
-            }
-        }
-        if pruned.is_empty() {  return 0;  }
-        self.changeWindowLayout = true;
-        self.resetWindows = true;
-        self.updated = true;
-
-        let mut numPruned = 0;
-        // pruned should be in ascending order
-        for index in &pruned {
-            self.PruneUpdate(*index, &mut numPruned);
-        } numPruned
-    }
-
-    /// Gets the names to all windows which contain at least one of the
-    /// specified keywords.
-    pub fn GetWindowsByKeywords (&self, keywords: Vec <String>) -> Vec <&String> {
-        let mut names = vec![];
-        for name in &self.windowReferences {
-            for keyword in &self.activeWindows[*name.1].1 {
-                if keywords.contains(keyword) {
-                    names.push(name.0);
-                    break;
-                }
-            }
-        }
-        names
-    }
-
-    pub fn GetWindowsByKeywordsNonRef (&self, keywords: Vec <String>) -> Vec <String> {
-        let mut names = vec![];
-        for name in &self.windowReferences {
-            for keyword in &self.activeWindows[*name.1].1 {
-                if keywords.contains(keyword) {
-                    names.push(name.0.clone());
-                    break;
-                }
-            }
-        } names
-    }
-
-    /// Gets the names to all windows which satisfy the given key (closure).
-    /// If the closure returns true, the name is provided. Otherwise, it's
-    /// considered unrelated.
-    pub fn GetWindowsByKey (&self, key: Box <dyn Fn (&Vec <String>) -> bool>) -> Vec <&String> {
-        let mut names = vec![];
-        for name in &self.windowReferences {
-            if key(&self.activeWindows[*name.1].1) {
-                names.push(name.0);
-            }
-        }
-        names
-    }
-
-    /// Checks if a given window contains a specific keyword.
-    pub fn WindowContainsKeyword (&self, windowName: &String, keyword: &String) -> bool {
-        let windowIndex = self.windowReferences[windowName];
-        self.activeWindows[windowIndex].1.contains(keyword)
-    }
-
-    pub fn ChangedWindowLayout (&self) -> bool {
-        self.changeWindowLayout
-    }
 }
 
@@ -0,0 +1,391 @@
+// Where the rendered frames go
+// The terminal backend writes them to stdout, while the headless one plays the escape codes back
+// into a grid in memory (as a terminal would), so the renderer and everything drawn with it can be
+// tested without a terminal
+//   e.g. let backend = HeadlessBackend::new((80, 24));
+//        let mut app = App::WithBackend(Box::new(backend.clone()));
+//        ...
+//        backend.GetSnapshot()
+
+use super::grid::Grid;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod headless;
+#[cfg(test)]
+pub use headless::HeadlessBackend;
+
+// frames per second the terminal is written to at most, unless it's changed
+pub static DEFAULT_FRAME_RATE: u32 = 60;
+
+pub trait Backend: std::fmt::Debug + Send {
+    // the size of the screen (columns, rows)
+    fn GetSize (&self) -> std::io::Result <(u16, u16)>;
+
+    // Hands over a finished frame to be shown (this shouldn't block on the output)
+    // Cleared is set when what's on the screen can't be relied on anymore (like after a resize)
+    fn Present (&mut self, frame: Arc <Grid>, cleared: bool);
+
+    // waits on anything still being written
+    fn Flush (&mut self) {}
+
+    // the most frames written per second (0 for no limit)
+    fn SetMaxFrameRate (&mut self, _frameRate: u32) {}
+
+    // how the frames written so far went (the drawing side is filled in by the app)
+    fn GetMetrics (&self) -> RenderMetrics;
+
+    // sets the screen up when the app starts, and puts it back when it closes
+    fn Enter (&mut self) {}
+    fn Leave (&mut self) {}
+}
+
+// timings and counts for debugging the renderer
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderMetrics {
+    // frames drawn and handed to the backend
+    pub framesDrawn: u64,
+    // frames written out, and ones replaced by a newer frame before they could be
+    pub framesWritten: u64,
+    pub framesDropped: u64,
+    // how long the last frame took to draw the windows, and to diff and write out
+    pub drawTime: Duration,
+    pub writeTime: Duration,
+    // the slowest a frame has taken to diff and write out
+    pub maxWriteTime: Duration,
+    // what the last frame wrote
+    pub cellsWritten: usize,
+    pub bytesWritten: usize,
+}
+
+impl std::fmt::Display for RenderMetrics {
+    fn fmt (&self, f: &mut std::fmt::Formatter <'_>) -> std::fmt::Result {
+        write!(f, "{} drawn, {} written, {} dropped; draw {:.2?}, write {:.2?} (max {:.2?}); last frame {} cells / {} bytes",
+            self.framesDrawn, self.framesWritten, self.framesDropped,
+            self.drawTime, self.writeTime, self.maxWriteTime,
+            self.cellsWritten, self.bytesWritten,
+        )
+    }
+}
+
+// The escape codes that take the screen from the previous frame to this one, starting over from a
+// blank screen when it was cleared. Returns how many cells were written
+fn GetFrameOutput (frame: &Grid, previous: &Grid, cleared: bool, output: &mut String) -> usize {
+    if cleared || previous.GetSize() != frame.GetSize() {
+        output.push_str("\x1b[0m\x1b[2J");
+        return frame.Diff(&Grid::new(frame.GetSize()), output);
+    }
+    frame.Diff(previous, output)
+}
+
+// diffs a frame against the last one and writes it, recording how it went
+fn WriteFrame (frame: &Grid, previous: &Grid, cleared: bool, metrics: &mut RenderMetrics, out: &mut impl Write) {
+    let start = Instant::now();
+    let mut output = String::new();
+    metrics.cellsWritten = GetFrameOutput(frame, previous, cleared, &mut output);
+    metrics.bytesWritten = output.len();
+    if !output.is_empty() {
+        // if the terminal's gone there isn't much to be done about it
+        let _ = out.write_all(output.as_bytes());
+        let _ = out.flush();
+    }
+    metrics.framesWritten += 1;
+    metrics.writeTime = start.elapsed();
+    metrics.maxWriteTime = metrics.maxWriteTime.max(metrics.writeTime);
+}
+
+enum WorkerMessage {
+    Frame (Arc <Grid>, bool),
+    FrameRate (u32),
+    // written back to once everything before it is on the screen
+    Flush (std::sync::mpsc::Sender <()>),
+}
+
+// The real terminal. Frames are written by a worker thread that lives as long as the backend
+// If frames come in faster than they're written (or than the frame rate allows), only the newest is
+// written; since each is diffed against whatever was written last, skipping some doesn't lose anything
+#[derive(Debug)]
+pub struct TerminalBackend {
+    sender: Option <std::sync::mpsc::Sender <WorkerMessage>>,
+    worker: Option <std::thread::JoinHandle <()>>,
+    metrics: Arc <parking_lot::Mutex <RenderMetrics>>,
+}
+
+impl Default for TerminalBackend {
+    fn default () -> Self {
+        TerminalBackend::new(DEFAULT_FRAME_RATE)
+    }
+}
+
+impl TerminalBackend {
+    pub fn new (frameRate: u32) -> Self {
+        TerminalBackend::Spawn(frameRate, std::io::stdout())
+    }
+
+    // starts the worker, writing to something other than stdout if need be
+    fn Spawn (frameRate: u32, out: impl Write + Send + 'static) -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let metrics = Arc::new(parking_lot::Mutex::new(RenderMetrics::default()));
+        let workerMetrics = metrics.clone();
+        let worker = std::thread::spawn(move || TerminalBackend::RunWorker(receiver, workerMetrics, frameRate, out));
+        TerminalBackend {
+            sender: Some(sender),
+            worker: Some(worker),
+            metrics,
+        }
+    }
+
+    fn GetFrameInterval (frameRate: u32) -> Duration {
+        match frameRate {
+            0 => Duration::ZERO,
+            _ => Duration::from_secs(1) / frameRate,
+        }
+    }
+
+    // runs until the backend is dropped (closing the channel)
+    fn RunWorker (receiver: std::sync::mpsc::Receiver <WorkerMessage>, metrics: Arc <parking_lot::Mutex <RenderMetrics>>, frameRate: u32, mut out: impl Write) {
+        let mut interval = TerminalBackend::GetFrameInterval(frameRate);
+        let mut previous = Grid::new((0, 0));
+        let mut lastWrite: Option <Instant> = None;
+        let mut pending: Option <(Arc <Grid>, bool)> = None;
+        let mut flushes = vec![];
+        loop {
+            // waiting on something to do, or for the frame rate to allow the pending frame to be written
+            let wait = match (&pending, lastWrite) {
+                (Some(_), Some(last)) => Some((last + interval).saturating_duration_since(Instant::now())),
+                (Some(_), None) => Some(Duration::ZERO),
+                (None, _) => None,
+            };
+            let message = match wait {
+                Some(wait) => match receiver.recv_timeout(wait) {
+                    Ok(message) => Some(message),
+                    Err(std::sync::mpsc::RecvTimeoutError::Timeout) => None,
+                    Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+                },
+                None => match receiver.recv() {
+                    Ok(message) => Some(message),
+                    Err(_) => break,
+                },
+            };
+            match message {
+                Some(WorkerMessage::Frame(frame, cleared)) => {
+                    // a frame that hasn't been written yet is stale, but if it cleared the screen the newer one has to as well
+                    if let Some((_, staleCleared)) = pending.take() {
+                        metrics.lock().framesDropped += 1;
+                        pending = Some((frame, cleared || staleCleared));
+                    } else {
+                        pending = Some((frame, cleared));
+                    }
+                    continue;
+                },
+                Some(WorkerMessage::FrameRate(frameRate)) => {
+                    interval = TerminalBackend::GetFrameInterval(frameRate);
+                    continue;
+                },
+                Some(WorkerMessage::Flush(done)) => {
+                    flushes.push(done);
+                    // anything sent before the flush is already in, so it goes out now without waiting on the frame rate
+                    while let Ok(message) = receiver.try_recv() {
+                        match message {
+                            WorkerMessage::Frame(frame, cleared) => {
+                                let staleCleared = pending.take().is_some_and(|(_, cleared)| cleared);
+                                pending = Some((frame, cleared || staleCleared));
+                            },
+                            WorkerMessage::FrameRate(frameRate) => interval = TerminalBackend::GetFrameInterval(frameRate),
+                            WorkerMessage::Flush(done) => flushes.push(done),
+                        }
+                    }
+                },
+                None => {},
+            }
+
+            if let Some((frame, cleared)) = pending.take() {
+                let mut frameMetrics = *metrics.lock();
+                WriteFrame(&frame, &previous, cleared, &mut frameMetrics, &mut out);
+                *metrics.lock() = frameMetrics;
+                previous = Grid::clone(&frame);
+                lastWrite = Some(Instant::now());
+            }
+            for done in flushes.drain(..) {
+                let _ = done.send(());
+            }
+        }
+    }
+
+    fn Send (&self, message: WorkerMessage) {
+        if let Some(sender) = &self.sender {
+            // the worker only stops once the sender's gone, so this can't really fail
+            let _ = sender.send(message);
+        }
+    }
+}
+
+impl Drop for TerminalBackend {
+    fn drop (&mut self) {
+        // closing the channel stops the worker
+        self.sender = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Backend for TerminalBackend {
+    fn GetSize (&self) -> std::io::Result <(u16, u16)> {
+        crossterm::terminal::size()
+    }
+
+    fn Present (&mut self, frame: Arc <Grid>, cleared: bool) {
+        self.Send(WorkerMessage::Frame(frame, cleared));
+    }
+
+    fn Flush (&mut self) {
+        let (done, waiting) = std::sync::mpsc::channel();
+        self.Send(WorkerMessage::Flush(done));
+        // if the worker's gone there's nothing left to wait on
+        let _ = waiting.recv();
+    }
+
+    fn SetMaxFrameRate (&mut self, frameRate: u32) {
+        self.Send(WorkerMessage::FrameRate(frameRate));
+    }
+
+    fn GetMetrics (&self) -> RenderMetrics {
+        *self.metrics.lock()
+    }
+
+    fn Enter (&mut self) {  // 1049h
+        print!("\x1B7");
+        print!("\x1B[?1049h");
+        print!("\x1B[?25l");
+    }
+
+    fn Leave (&mut self) {
+        self.Flush();
+        print!("{}", super::SHOW_CURSOR);  // showing the cursor
+
+        // clearing the screen
+        //print!("\x1B[2J\x1B[H\x1b");
+        print!("\x1B[0m");
+        print!("\x1B[?1049l");
+        print!("\x1B[2K\x1B[E");
+        print!("\x1Bc");
+
+        // I don't really care if an error is thrown at this point
+        let _ = std::io::stdout().flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{App, Colorize, ColorType, Span, Window};
+
+    fn NewWindow (position: (u16, u16), depth: u16, size: (u16, u16), lines: &[&str]) -> Window {
+        let mut window = Window::new(position, depth, size);
+        window.Bordered();
+        for line in lines {
+            window.AddLine(Span::FromTokens(vec![line.Colorizes(vec![ColorType::Red])]));
+        }
+        window
+    }
+
+    #[test]
+    fn DrawsWindowsIntoTheScreen () {
+        let backend = HeadlessBackend::new((12, 4));
+        let mut app = App::WithBackend(Box::new(backend.clone()));
+        app.AddWindow(NewWindow((0, 0), 0, (8, 4), &["hi", "漢字"]), String::from("Text"), vec![]);
+        app.Render(None);
+
+        assert_eq!(backend.GetLines(), vec![
+            "┌──────┐    ",
+            "│hi    │    ",
+            "│漢字  │    ",
+            "└──────┘    ",
+        ]);
+        assert_eq!(backend.GetSnapshot(), [
+            "┌──────┐",
+            "│{31}hi{}    │",
+            "│{31}漢字{}  │",
+            "└──────┘",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn MatchesTheRenderersScreen () {
+        let backend = HeadlessBackend::new((20, 8));
+        let mut app = App::WithBackend(Box::new(backend.clone()));
+        app.AddWindow(NewWindow((0, 0), 0, (14, 6), &["underneath", "漢字漢字漢字"]), String::from("Bottom"), vec![]);
+        app.AddWindow(NewWindow((5, 1), 1, (10, 4), &["on top"]), String::from("Top"), vec![]);
+        app.Render(None);
+        assert_eq!(Some(&backend.GetScreen()), app.GetScreen());
+
+        // moving the top window only writes what changed, which still has to end up the same
+        let written = backend.GetWritten();
+        app.GetWindowReferenceMut(String::from("Top")).Move((6, 2));
+        assert!(app.Render(None));
+        let changed = app.GetMetrics().cellsWritten;
+        assert!(changed > 0 && changed < 20 * 8 / 2);
+        assert!(backend.GetWritten() > written);
+        assert_eq!(Some(&backend.GetScreen()), app.GetScreen());
+
+        let written = backend.GetWritten();
+        assert!(!app.Render(None));
+        assert_eq!(backend.GetWritten(), written);
+        assert_eq!(app.GetMetrics().framesDrawn, 2);
+    }
+
+    #[test]
+    fn ClearsOnResize () {
+        let backend = HeadlessBackend::new((10, 3));
+        let mut app = App::WithBackend(Box::new(backend.clone()));
+        app.AddWindow(NewWindow((0, 0), 0, (10, 3), &["wide"]), String::from("Text"), vec![]);
+        app.Render(None);
+
+        backend.Resize((6, 3));
+        app.GetWindowReferenceMut(String::from("Text")).Resize((6, 3));
+        app.Render(None);
+        assert_eq!(backend.GetLines(), vec!["┌────┐", "│wide│", "└────┘"]);
+        assert_eq!(Some(&backend.GetScreen()), app.GetScreen());
+    }
+
+    // a writer the test can still read from after it's given to the worker
+    #[derive(Clone, Default)]
+    struct SharedOutput (Arc <parking_lot::Mutex <Vec <u8>>>);
+
+    impl Write for SharedOutput {
+        fn write (&mut self, bytes: &[u8]) -> std::io::Result <usize> {
+            self.0.lock().extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush (&mut self) -> std::io::Result <()> {
+            Ok(())
+        }
+    }
+
+    fn NewFrame (text: &str) -> Arc <Grid> {
+        let mut grid = Grid::new((10, 1));
+        grid.DrawSpan(0, 0, &Span::FromTokens(vec![text.Colorizes(vec![])]), 0, 10);
+        Arc::new(grid)
+    }
+
+    #[test]
+    fn WorkerDropsStaleFrames () {
+        let output = SharedOutput::default();
+        // slow enough that the frames after the first are still waiting when the flush comes in
+        let mut backend = TerminalBackend::Spawn(1, output.clone());
+        backend.Present(NewFrame("first"), true);
+        backend.Flush();
+        backend.Present(NewFrame("stale"), false);
+        backend.Present(NewFrame("newest"), false);
+        backend.Flush();
+
+        let metrics = backend.GetMetrics();
+        assert_eq!((metrics.framesWritten, metrics.framesDropped), (2, 1));
+        let written = String::from_utf8(output.0.lock().clone()).unwrap();
+        assert!(written.contains("first") && written.contains("newest") && !written.contains("stale"));
+    }
+}
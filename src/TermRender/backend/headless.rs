@@ -0,0 +1,143 @@
+// The headless backend, which plays the escape codes back into a grid in memory (only built for tests)
+
+use super::*;
+use super::super::grid::CellStyle;
+use unicode_segmentation::UnicodeSegmentation;
+
+// A screen in memory, which can be cloned to keep a handle on it after it's given to the app
+// Each frame is written straight away (there's no worker), and only what the renderer writes is
+// understood: moving the cursor, setting the style, clearing the screen and printing text
+#[derive(Clone, Debug)]
+pub struct HeadlessBackend {
+    screen: Arc <parking_lot::Mutex <HeadlessScreen>>,
+}
+
+#[derive(Debug)]
+struct HeadlessScreen {
+    grid: Grid,
+    // the last frame presented, which the next is diffed against
+    previous: Grid,
+    cursor: (usize, usize),
+    style: CellStyle,
+    // the grapheme being printed, which may still have more characters coming (accents, emoji joiners...)
+    grapheme: String,
+    metrics: RenderMetrics,
+    // everything written, for checking what a frame actually sent
+    written: usize,
+}
+
+impl HeadlessBackend {
+    pub fn new (size: (u16, u16)) -> Self {
+        let grid = Grid::new(size);
+        HeadlessBackend {
+            screen: Arc::new(parking_lot::Mutex::new(HeadlessScreen {
+                style: grid.GetClearStyle(),
+                previous: grid.clone(),
+                grid,
+                cursor: (0, 0),
+                grapheme: String::new(),
+                metrics: RenderMetrics::default(),
+                written: 0,
+            })),
+        }
+    }
+
+    // resizing a terminal leaves its contents in a state that can't be relied on, so it starts out blank
+    pub fn Resize (&self, size: (u16, u16)) {
+        let mut screen = self.screen.lock();
+        screen.grid = Grid::new(size);
+        screen.cursor = (0, 0);
+    }
+
+    // what's on the screen
+    pub fn GetScreen (&self) -> Grid {
+        self.screen.lock().grid.clone()
+    }
+
+    pub fn GetLines (&self) -> Vec <String> {
+        self.screen.lock().grid.GetLines()
+    }
+
+    // the screen as text with its colors (see Grid::GetSnapshot)
+    pub fn GetSnapshot (&self) -> String {
+        self.screen.lock().grid.GetSnapshot()
+    }
+
+    // how many bytes have been written so far
+    pub fn GetWritten (&self) -> usize {
+        self.screen.lock().written
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn GetSize (&self) -> std::io::Result <(u16, u16)> {
+        Ok(self.screen.lock().grid.GetSize())
+    }
+
+    fn Present (&mut self, frame: Arc <Grid>, cleared: bool) {
+        let mut screen = self.screen.lock();
+        let screen = &mut *screen;
+        let mut output = vec![];
+        WriteFrame(&frame, &screen.previous, cleared, &mut screen.metrics, &mut output);
+        screen.previous = Grid::clone(&frame);
+        screen.written += output.len();
+        let mut parser = vte::Parser::new();
+        parser.advance(screen, &output);
+        screen.PrintGrapheme();
+    }
+
+    fn GetMetrics (&self) -> RenderMetrics {
+        self.screen.lock().metrics
+    }
+}
+
+impl HeadlessScreen {
+    // puts down the grapheme being built up, moving the cursor past it
+    fn PrintGrapheme (&mut self) {
+        if self.grapheme.is_empty() {  return;  }
+        let grapheme = std::mem::take(&mut self.grapheme);
+        let style = self.style.clone();
+        self.cursor.0 += self.grid.Set(self.cursor.0, self.cursor.1, &grapheme, &style);
+    }
+}
+
+impl vte::Perform for HeadlessScreen {
+    fn print (&mut self, chr: char) {
+        self.grapheme.push(chr);
+        if self.grapheme.graphemes(true).count() > 1 {
+            self.grapheme.pop();
+            self.PrintGrapheme();
+            self.grapheme.push(chr);
+        }
+    }
+
+    fn execute (&mut self, _byte: u8) {
+        self.PrintGrapheme();
+    }
+
+    fn csi_dispatch (&mut self, params: &vte::Params, intermediates: &[u8], _ignore: bool, action: char) {
+        self.PrintGrapheme();
+        if !intermediates.is_empty() {  return;  }  // private modes (hiding the cursor and such)
+        let numbers: Vec <u16> = params.iter().map(|param| param[0]).collect();
+        match action {
+            // ESC[{line};{column}H
+            'H' => {
+                let row = numbers.first().copied().unwrap_or(1).max(1) as usize - 1;
+                let column = numbers.get(1).copied().unwrap_or(1).max(1) as usize - 1;
+                self.cursor = (column, row);
+            },
+            'm' => {
+                let codes: Vec <String> = numbers.iter().map(|number| number.to_string()).collect();
+                self.style = format!("\x1b[{}m", codes.join(";")).into();
+            },
+            'J' if numbers.first() == Some(&2) => {
+                self.grid = Grid::new(self.grid.GetSize());
+            },
+            _ => {},
+        }
+    }
+
+    fn esc_dispatch (&mut self, _intermediates: &[u8], _ignore: bool, _byte: u8) {
+        self.PrintGrapheme();
+    }
+}
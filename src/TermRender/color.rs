@@ -0,0 +1,194 @@
+// What colors the terminal can show, and bringing RGB/256 palette colors down to fit it
+// Truecolor terminals get RGB colors as they are, others get the closest color in the 256 color
+// palette, or in the basic 16 for terminals that only have those
+//   e.g. ColorType::Rgb(255, 135, 0) is sent as 38;2;255;135;0, 38;5;208 or 33 (yellow)
+
+// the support is found once (from COLORTERM and TERM)
+static SUPPORT: std::sync::LazyLock <ColorSupport> = std::sync::LazyLock::new(ColorSupport::Detect);
+
+// the levels each channel of the 6x6x6 cube (16 through 231) can take
+static CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+// the usual (xterm) values of the basic 16 colors; terminals are free to change these, so it's only an estimate
+static BASIC_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+    (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+    (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default, PartialOrd, Ord)]
+pub enum ColorSupport {
+    // the 16 base colors
+    #[default] Basic,
+    // the 256 color palette
+    Indexed,
+    // any RGB color
+    TrueColor,
+}
+
+impl ColorSupport {
+    pub fn Current () -> ColorSupport {
+        *SUPPORT
+    }
+
+    pub fn Detect () -> ColorSupport {
+        ColorSupport::FromEnvironment(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    // COLORTERM is set to truecolor/24bit by terminals that take RGB colors,
+    // and TERM usually ends in -256color (or -direct for RGB) otherwise
+    pub fn FromEnvironment (colorTerm: Option <&str>, term: Option <&str>) -> ColorSupport {
+        if let Some(colorTerm) = colorTerm &&
+            (colorTerm.eq_ignore_ascii_case("truecolor") || colorTerm.eq_ignore_ascii_case("24bit")) {
+            return ColorSupport::TrueColor;
+        }
+        match term {
+            Some(term) if term.ends_with("direct") || term.contains("truecolor") => ColorSupport::TrueColor,
+            Some(term) if term.contains("256color") => ColorSupport::Indexed,
+            _ => ColorSupport::Basic,
+        }
+    }
+}
+
+fn GetDistance (first: (u8, u8, u8), second: (u8, u8, u8)) -> u32 {
+    let difference = |a: u8, b: u8| (a as i32 - b as i32).unsigned_abs();
+    difference(first.0, second.0).pow(2) + difference(first.1, second.1).pow(2) + difference(first.2, second.2).pow(2)
+}
+
+// the index of the cube level closest to the channel's value
+fn GetCubeLevel (value: u8) -> usize {
+    CUBE_LEVELS.iter().enumerate()
+        .min_by_key(|(_, level)| (**level as i32 - value as i32).abs())
+        .map_or(0, |(index, _)| index)
+}
+
+// the RGB value of a color in the 256 color palette
+pub fn IndexedToRgb (index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => BASIC_COLORS[index as usize],
+        16..=231 => {
+            let index = (index - 16) as usize;
+            (CUBE_LEVELS[index / 36], CUBE_LEVELS[index / 6 % 6], CUBE_LEVELS[index % 6])
+        },
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        },
+    }
+}
+
+// the closest color in the 256 color palette, from either the cube or the gray ramp
+// (the basic 16 are skipped since terminals often change them)
+pub fn RgbToIndexed (rgb: (u8, u8, u8)) -> u8 {
+    let levels = (GetCubeLevel(rgb.0), GetCubeLevel(rgb.1), GetCubeLevel(rgb.2));
+    let cube = (16 + levels.0 * 36 + levels.1 * 6 + levels.2) as u8;
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+    match GetDistance(rgb, IndexedToRgb(gray)) < GetDistance(rgb, IndexedToRgb(cube)) {
+        true => gray,
+        false => cube,
+    }
+}
+
+// the closest of the basic 16 colors (0 through 7, then the bright versions 8 through 15)
+pub fn RgbToBasic (rgb: (u8, u8, u8)) -> u8 {
+    BASIC_COLORS.iter().enumerate()
+        .min_by_key(|(_, color)| GetDistance(rgb, **color))
+        .map_or(0, |(index, _)| index as u8)
+}
+
+pub fn IndexedToBasic (index: u8) -> u8 {
+    match index {
+        0..=15 => index,
+        _ => RgbToBasic(IndexedToRgb(index)),
+    }
+}
+
+// the SGR code for one of the basic 16 colors
+pub fn GetBasicCode (index: u8, background: bool) -> String {
+    match (index < 8, background) {
+        (true, false) => format!("{}", 30 + index),
+        (true, true) => format!("{}", 40 + index),
+        (false, false) => format!("{}", 90 + index - 8),
+        (false, true) => format!("{}", 100 + index - 8),
+    }
+}
+
+// the SGR code for an RGB color, brought down to what the terminal supports
+pub fn GetRgbCode (rgb: (u8, u8, u8), background: bool) -> String {
+    let layer = if background { 48 } else { 38 };
+    match ColorSupport::Current() {
+        ColorSupport::TrueColor => format!("{};2;{};{};{}", layer, rgb.0, rgb.1, rgb.2),
+        ColorSupport::Indexed => format!("{};5;{}", layer, RgbToIndexed(rgb)),
+        ColorSupport::Basic => GetBasicCode(RgbToBasic(rgb), background),
+    }
+}
+
+// the SGR code for a color in the 256 color palette, brought down to what the terminal supports
+pub fn GetIndexedCode (index: u8, background: bool) -> String {
+    let layer = if background { 48 } else { 38 };
+    match ColorSupport::Current() {
+        ColorSupport::TrueColor | ColorSupport::Indexed => format!("{};5;{}", layer, index),
+        ColorSupport::Basic => GetBasicCode(IndexedToBasic(index), background),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn PicksTheCubeOrTheGrayRamp () {
+        // colors in the cube land on it exactly
+        assert_eq!(RgbToIndexed((255, 135, 0)), 208);
+        assert_eq!(RgbToIndexed((255, 0, 0)), 196);
+        assert_eq!(RgbToIndexed((0, 0, 0)), 16);
+        assert_eq!(RgbToIndexed((95, 95, 95)), 59);
+        // grays between the cube's levels are closer on the ramp
+        assert_eq!(RgbToIndexed((128, 128, 128)), 244);
+        assert_eq!(RgbToIndexed((100, 100, 100)), 241);
+        assert_eq!(RgbToIndexed((238, 238, 238)), 255);
+    }
+
+    #[test]
+    fn PicksTheClosestBasicColor () {
+        assert_eq!(RgbToBasic((255, 135, 0)), 3);
+        assert_eq!(RgbToBasic((250, 10, 10)), 9);
+        assert_eq!(RgbToBasic((10, 10, 10)), 0);
+        assert_eq!(RgbToBasic((240, 240, 240)), 7);
+        assert_eq!(RgbToBasic((250, 250, 250)), 15);
+    }
+
+    #[test]
+    fn BringsIndexedColorsDownToBasic () {
+        // the basic 16 are already there
+        assert_eq!(IndexedToBasic(5), 5);
+        assert_eq!(IndexedToBasic(9), 9);
+        assert_eq!(IndexedToBasic(196), 9);
+        assert_eq!(IndexedToBasic(16), 0);
+        assert_eq!(IndexedToBasic(231), 15);
+        assert_eq!(IndexedToBasic(244), 8);
+    }
+
+    #[test]
+    fn DetectsSupportFromTheEnvironment () {
+        let expected = [
+            ((Some("truecolor"), Some("xterm")), ColorSupport::TrueColor),
+            ((Some("24BIT"), None), ColorSupport::TrueColor),
+            // COLORTERM is set to other things too, in which case TERM decides
+            ((Some("yes"), Some("screen-256color")), ColorSupport::Indexed),
+            ((Some(""), Some("linux")), ColorSupport::Basic),
+            ((None, Some("xterm-direct")), ColorSupport::TrueColor),
+            ((None, Some("xterm-256color")), ColorSupport::Indexed),
+            ((None, Some("xterm")), ColorSupport::Basic),
+            ((None, None), ColorSupport::Basic),
+        ];
+        for ((colorTerm, term), support) in expected {
+            assert_eq!(ColorSupport::FromEnvironment(colorTerm, term), support, "COLORTERM={:?} TERM={:?}", colorTerm, term);
+        }
+    }
+}
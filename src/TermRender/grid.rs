@@ -0,0 +1,231 @@
+// A grid of styled cells the size of the terminal, which the windows are drawn into
+// Each frame every visible window is drawn (lowest depth first) into a fresh grid, which is then
+// compared with the one already on screen so only the cells that changed get written out.
+// Whatever's on top simply wins, so windows overlapping (or being moved/removed from over each
+// other) can't leave anything half drawn behind.
+
+use super::{Span, GetDisplayWidth, CLEAR};
+use unicode_segmentation::UnicodeSegmentation;
+
+// the escape code for a cell's colors; it always starts with a reset so it doesn't depend on what came before
+pub type CellStyle = std::sync::Arc <str>;
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Cell {
+    // a single grapheme, or nothing for the second half of a wide one
+    text: String,
+    style: CellStyle,
+}
+
+impl Cell {
+    fn Blank (style: &CellStyle) -> Self {
+        Cell {
+            text: String::from(" "),
+            style: style.clone(),
+        }
+    }
+
+    // how many cells the grapheme takes up (0 for the second half of a wide one)
+    pub fn GetWidth (&self) -> usize {
+        match self.text.is_empty() {
+            true => 0,
+            false => GetDisplayWidth(&self.text).clamp(1, 2),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Grid {
+    size: (u16, u16),
+    cells: Vec <Cell>,
+    // shared by every blank cell
+    clear: CellStyle,
+}
+
+impl Grid {
+    // a grid of blank cells, which is also what the screen looks like right after it's cleared
+    pub fn new (size: (u16, u16)) -> Self {
+        let clear: CellStyle = CLEAR.into();
+        Grid {
+            size,
+            cells: vec![Cell::Blank(&clear); size.0 as usize * size.1 as usize],
+            clear,
+        }
+    }
+
+    pub fn GetSize (&self) -> (u16, u16) {
+        self.size
+    }
+
+    // the style of a blank cell (no colors)
+    pub fn GetClearStyle (&self) -> CellStyle {
+        self.clear.clone()
+    }
+
+    fn GetIndex (&self, column: usize, row: usize) -> Option <usize> {
+        match column < self.size.0 as usize && row < self.size.1 as usize {
+            true => Some(row * self.size.0 as usize + column),
+            false => None,
+        }
+    }
+
+    // Blanks out the other half of a wide grapheme about to be partly covered, so half of one is never left behind
+    fn BreakWide (&mut self, column: usize, row: usize) {
+        let Some(index) = self.GetIndex(column, row) else {  return;  };
+        if self.cells[index].text.is_empty() && column > 0 {
+            self.cells[index - 1].text = String::from(" ");
+        }
+        if self.cells[index].GetWidth() == 2 && let Some(next) = self.GetIndex(column + 1, row) {
+            self.cells[next].text = String::from(" ");
+        }
+    }
+
+    // Writes a grapheme, returning how many cells it took up
+    // Anything off the grid is dropped, and a wide grapheme without room for both halves becomes a space
+    pub fn Set (&mut self, column: usize, row: usize, text: &str, style: &CellStyle) -> usize {
+        let width = GetDisplayWidth(text).clamp(1, 2);
+        let Some(index) = self.GetIndex(column, row) else {  return width;  };
+        if width == 2 && column + 1 >= self.size.0 as usize {
+            self.BreakWide(column, row);
+            self.cells[index] = Cell::Blank(style);
+            return width;
+        }
+        for offset in 0..width {
+            self.BreakWide(column + offset, row);
+        }
+        self.cells[index] = Cell {
+            text: text.to_string(),
+            style: style.clone(),
+        };
+        if width == 2 {
+            self.cells[index + 1] = Cell {
+                text: String::new(),
+                style: style.clone(),
+            };
+        }
+        width
+    }
+
+    // writes the same grapheme over a number of cells
+    pub fn Fill (&mut self, column: usize, row: usize, count: usize, text: &str, style: &CellStyle) {
+        let mut used = 0;
+        while used < count {
+            used += self.Set(column + used, row, text, style);
+        }
+    }
+
+    // Writes a span, skipping its first few cells (for scrolling sideways) and stopping at the width
+    // A wide grapheme cut in half by either end becomes spaces. Returns how many cells were written
+    pub fn DrawSpan (&mut self, column: usize, row: usize, span: &Span, skip: usize, width: usize) -> usize {
+        let mut skipped = 0;
+        let mut used = 0;
+        for token in &span.line {
+            let style: CellStyle = token.GetStyle().into();
+            for grapheme in token.text.graphemes(true) {
+                let size = GetDisplayWidth(grapheme);
+                if size == 0 {  continue;  }
+                if skipped < skip {
+                    skipped += size;
+                    for _ in skip..skipped {
+                        if used >= width {  return used;  }
+                        used += self.Set(column + used, row, " ", &style);
+                    }
+                    continue;
+                }
+                if used + size > width {
+                    while used < width {
+                        used += self.Set(column + used, row, " ", &style);
+                    }
+                    return used;
+                }
+                used += self.Set(column + used, row, grapheme, &style);
+            }
+        }
+        used
+    }
+
+    // Writes out the escape codes that turn the previous grid into this one, only touching the cells that changed
+    // Both grids have to be the same size. Returns how many cells were written
+    pub fn Diff (&self, previous: &Grid, output: &mut String) -> usize {
+        let width = self.size.0 as usize;
+        let mut cursor = None;
+        let mut lastStyle: Option <&CellStyle> = None;
+        let mut written = 0;
+        for row in 0..self.size.1 as usize {
+            let mut column = 0;
+            while column < width {
+                let index = row * width + column;
+                let cell = &self.cells[index];
+                let size = cell.GetWidth().max(1);
+                let end = std::cmp::min(index + size, (row + 1) * width);
+                if cell.text.is_empty() || self.cells[index..end] == previous.cells[index..end] {
+                    column += size;
+                    continue;
+                }
+
+                // ESC[{line};{column}H, unless the cursor is already there from the last cell
+                if cursor != Some((column, row)) {
+                    output.push_str("\x1b[");
+                    super::App::PushU16(output, row as u16 + 1);
+                    output.push(';');
+                    super::App::PushU16(output, column as u16 + 1);
+                    output.push('H');
+                }
+                if lastStyle != Some(&cell.style) {
+                    output.push_str(&cell.style);
+                    lastStyle = Some(&cell.style);
+                }
+                output.push_str(&cell.text);
+                written += 1;
+
+                // writing in the last column leaves the cursor waiting to wrap, so it's moved by hand after
+                column += size;
+                cursor = match column < width {
+                    true => Some((column, row)),
+                    false => None,
+                };
+            }
+        }
+        if written > 0 {
+            output.push_str(CLEAR);
+        }
+        written
+    }
+
+    // the grid as plain text, one string per row (the second halves of wide graphemes are skipped)
+    #[cfg(test)]
+    pub fn GetLines (&self) -> Vec <String> {
+        self.cells.chunks(std::cmp::max(self.size.0 as usize, 1))
+            .take(self.size.1 as usize)
+            .map(|row| row.iter().map(|cell| cell.text.as_str()).collect())
+            .collect()
+    }
+
+    // The grid as text with its colors, for comparing against in tests
+    // Wherever the style changes its SGR codes are written in braces (without the leading reset), with {}
+    // being no colors, and trailing blanks are dropped
+    //   e.g. "│{31}hi{}    │" for a red "hi"
+    #[cfg(test)]
+    pub fn GetSnapshot (&self) -> String {
+        let mut rows = vec![];
+        for row in self.cells.chunks(std::cmp::max(self.size.0 as usize, 1)).take(self.size.1 as usize) {
+            let end = row.iter().rposition(|cell| *cell != Cell::Blank(&self.clear)).map_or(0, |index| index + 1);
+            let mut text = String::new();
+            let mut lastStyle = &self.clear;
+            for cell in &row[..end] {
+                if cell.style != *lastStyle {
+                    let codes = cell.style.trim_start_matches("\x1b[").trim_end_matches('m');
+                    let codes = codes.strip_prefix("0;").unwrap_or(codes);
+                    text.push('{');
+                    if codes != "0" {  text.push_str(codes);  }
+                    text.push('}');
+                    lastStyle = &cell.style;
+                }
+                text.push_str(&cell.text);
+            }
+            if *lastStyle != self.clear {  text.push_str("{}");  }
+            rows.push(text);
+        }
+        rows.join("\n")
+    }
+}
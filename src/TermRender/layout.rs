@@ -0,0 +1,205 @@
+// Splits areas of the screen into rows or columns, so windows can be placed relative to the
+// terminal's size instead of by hand
+//   e.g. Layout::Columns(vec![Constraint::Fixed(50), Constraint::Fill(1)]).Split(screen)
+// gives a 50 wide column on the left and whatever's left over on the right
+
+use super::Rect;
+
+// a place on the screen
+// like a window, the position is the top left corner with the screen starting at (1, 1)
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default, Hash)]
+pub struct Area {
+    pub position: (u16, u16),
+    pub size: (u16, u16),
+}
+
+impl Area {
+    pub fn new (position: (u16, u16), size: (u16, u16)) -> Self {
+        Area { position, size }
+    }
+
+    // the whole terminal
+    pub fn FromRect (rect: &Rect) -> Self {
+        Area {
+            position: (1, 1),
+            size: (rect.width, rect.height),
+        }
+    }
+
+    // the area with a margin taken off of every side
+    pub fn Inner (&self, margin: u16) -> Self {
+        Area {
+            position: (self.position.0 + margin, self.position.1 + margin),
+            size: (self.size.0.saturating_sub(margin * 2), self.size.1.saturating_sub(margin * 2)),
+        }
+    }
+}
+
+// how much space a single row or column takes up
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Constraint {
+    // exactly this many cells
+    Fixed (u16),
+    // a share of any space left over, weighted against the other fills
+    Fill (u16),
+}
+
+impl Constraint {
+    // the size before any left over space is handed out
+    fn GetBase (&self) -> u32 {
+        match self {
+            Constraint::Fixed(size) => *size as u32,
+            Constraint::Fill(_) => 0,
+        }
+    }
+
+    // how big a share of the left over space it gets
+    fn GetWeight (&self) -> u32 {
+        match self {
+            Constraint::Fill(weight) => *weight as u32,
+            Constraint::Fixed(_) => 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Direction {
+    // stacked top to bottom
+    Rows,
+    // side by side, left to right
+    Columns,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Layout {
+    direction: Direction,
+    constraints: Vec <Constraint>,
+    overlap: u16,
+}
+
+impl Layout {
+    pub fn new (direction: Direction, constraints: Vec <Constraint>) -> Self {
+        Layout {
+            direction,
+            constraints,
+            overlap: 0,
+        }
+    }
+
+    pub fn Rows (constraints: Vec <Constraint>) -> Self {
+        Layout::new(Direction::Rows, constraints)
+    }
+
+    pub fn Columns (constraints: Vec <Constraint>) -> Self {
+        Layout::new(Direction::Columns, constraints)
+    }
+
+    // how many cells neighbouring areas share (1 lets bordered windows share a border)
+    pub fn Overlap (mut self, overlap: u16) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    // Splits the area into one area per constraint
+    // If the constraints don't fit, the later areas are cut short (down to nothing) first
+    pub fn Split (&self, area: Area) -> Vec <Area> {
+        let length = match self.direction {
+            Direction::Rows => area.size.1,
+            Direction::Columns => area.size.0,
+        };
+        let mut areas = vec![];
+        let mut offset = 0;
+        for size in self.GetSizes(length) {
+            areas.push(match self.direction {
+                Direction::Rows => Area::new((area.position.0, area.position.1 + offset), (area.size.0, size)),
+                Direction::Columns => Area::new((area.position.0 + offset, area.position.1), (size, area.size.1)),
+            });
+            offset += size.saturating_sub(self.overlap);
+        }
+        areas
+    }
+
+    // Split, for when the number of areas is known
+    //   e.g. let [left, right] = Layout::Columns(...).Areas(screen);
+    pub fn Areas <const N: usize> (&self, area: Area) -> [Area; N] {
+        self.Split(area).try_into().unwrap_or_else(|areas: Vec <Area>| {
+            panic!("Layout has {} constraints, but {} areas were asked for", areas.len(), N)
+        })
+    }
+
+    // the length of each area along the direction being split
+    fn GetSizes (&self, length: u16) -> Vec <u16> {
+        // overlapping areas each get that much extra to cover the shared cells
+        let shared = self.overlap as u32 * self.constraints.len().saturating_sub(1) as u32;
+        let mut remaining = length as u32 + shared;
+        let mut sizes = vec![];
+        for constraint in &self.constraints {
+            let size = std::cmp::min(constraint.GetBase(), remaining);
+            remaining -= size;
+            sizes.push(size);
+        }
+
+        // handing out what's left to the fills by weight, with any cells lost to rounding going to the first ones
+        let growing: Vec <usize> = (0..sizes.len()).filter(|index| self.constraints[*index].GetWeight() > 0).collect();
+        let weights: u32 = growing.iter().map(|index| self.constraints[*index].GetWeight()).sum();
+        let mut given = 0;
+        for index in &growing {
+            let extra = remaining * self.constraints[*index].GetWeight() / weights;
+            sizes[*index] += extra;
+            given += extra;
+        }
+        for index in growing.iter().take((remaining - given) as usize) {
+            sizes[*index] += 1;
+        }
+
+        // the extra for the overlap is only right once every area has room, so anything that would then reach
+        // past the end is cut short (Split doesn't overlap areas left smaller than the overlap)
+        let mut offset = 0;
+        for size in sizes.iter_mut() {
+            *size = std::cmp::min(*size, (length as u32).saturating_sub(offset));
+            offset += size.saturating_sub(self.overlap as u32);
+        }
+
+        sizes.into_iter().map(|size| size as u16).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Constraint::{Fill, Fixed};
+
+    #[test]
+    fn SharesTheSpaceByWeight () {
+        assert_eq!(Layout::Columns(vec![Fill(1), Fill(3)]).GetSizes(40), [10, 30]);
+        assert_eq!(Layout::Columns(vec![Fixed(10), Fill(1), Fixed(5), Fill(2)]).GetSizes(45), [10, 10, 5, 20]);
+    }
+
+    #[test]
+    fn GivesRoundingToTheFirstFills () {
+        assert_eq!(Layout::Rows(vec![Fill(1), Fill(1), Fill(1)]).GetSizes(10), [4, 3, 3]);
+        assert_eq!(Layout::Rows(vec![Fixed(2), Fill(1), Fill(1)]).GetSizes(5), [2, 2, 1]);
+    }
+
+    #[test]
+    fn CutsTheLaterAreasShortFirst () {
+        assert_eq!(Layout::Columns(vec![Fixed(6), Fixed(6), Fill(1)]).GetSizes(8), [6, 2, 0]);
+        assert_eq!(Layout::Columns(vec![Fixed(24), Fill(1), Fixed(26)]).GetSizes(20), [20, 0, 0]);
+        assert_eq!(Layout::Columns(vec![Fill(1), Fixed(4)]).GetSizes(0), [0, 0]);
+    }
+
+    #[test]
+    fn OverlapsNeighbours () {
+        let layout = Layout::Columns(vec![Fixed(5), Fill(1), Fill(1)]).Overlap(1);
+        assert_eq!(layout.GetSizes(10), [5, 4, 3]);
+        let areas = layout.Split(Area::new((1, 1), (10, 3)));
+        assert_eq!(areas.iter().map(|area| area.position.0).collect::<Vec <_>>(), [1, 5, 8]);
+
+        // smaller than the overlap needs, nothing reaches past the end
+        for length in 0..12 {
+            for area in layout.Split(Area::new((1, 1), (length, 3))) {
+                assert!(area.position.0 - 1 + area.size.0 <= length, "{:?} is outside of {}", area, length);
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// Named colors for what things are (a border, a title, the selected line...) rather than what they look like,
+// so a whole interface can be recolored at once
+//   e.g. "Title".Stylize(&Theme::Current().title)
+// The current theme is global and can be swapped at any point; anything drawn after that picks up the new colors.
+// Themes can be read from json, where each role is a list of color types:
+//   { "text": ["Black"], "muted": ["BrightBlack", "Italic"], "positive": [{ "Rgb": [0, 135, 0] }] }
+// Any role left out keeps the default (dark) colors.
+
+use super::ColorType;
+
+// a set of colors/modifiers applied together
+pub type Style = Vec <ColorType>;
+
+static CURRENT: std::sync::LazyLock <parking_lot::RwLock <std::sync::Arc <Theme>>> =
+    std::sync::LazyLock::new(|| parking_lot::RwLock::new(std::sync::Arc::new(Theme::default())));
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    // plain text
+    pub text: Style,
+    // hints, placeholders and anything else that should fade into the background
+    pub muted: Style,
+    // names and labels next to values
+    pub label: Style,
+    // the headings of sections and fields
+    pub heading: Style,
+    // window titles
+    pub title: Style,
+    // text that should stand out (names, button labels)
+    pub strong: Style,
+    // the borders of pop ups
+    pub border: Style,
+    // buttons that sit on the screen (their whole window)
+    pub button: Style,
+    // the background of the selected or hovered line
+    pub selection: Style,
+    // whatever has the focus (a heading, a button, the chosen suggestion)
+    pub highlight: Style,
+    // a form's heading while its field has the focus
+    pub focused_heading: Style,
+    // the row naming a table's columns
+    pub column_heading: Style,
+    // text that's being edited or an option that's turned on
+    pub active: Style,
+    // the text cursor
+    pub cursor: Style,
+    // a slider's knob and the track it sits on
+    pub knob: Style,
+    pub track: Style,
+    // good and bad (a completed habit, a helpful food...)
+    pub positive: Style,
+    pub negative: Style,
+    // a rating, from the lowest value to the highest (spread evenly over the range)
+    pub mood: Vec <Style>,
+}
+
+impl Default for Theme {
+    fn default () -> Self {
+        Theme {
+            text: vec![ColorType::White],
+            muted: vec![ColorType::BrightBlack, ColorType::Italic],
+            label: vec![ColorType::White, ColorType::Italic],
+            heading: vec![ColorType::BrightWhite, ColorType::Italic],
+            title: vec![ColorType::BrightWhite, ColorType::Bold],
+            strong: vec![ColorType::BrightWhite, ColorType::Bold],
+            border: vec![ColorType::Bold],
+            button: vec![ColorType::White, ColorType::OnBrightBlack],
+            selection: vec![ColorType::OnBrightBlack],
+            highlight: vec![ColorType::Black, ColorType::OnWhite],
+            focused_heading: vec![ColorType::Black, ColorType::OnWhite, ColorType::Italic],
+            column_heading: vec![ColorType::White, ColorType::Underline],
+            active: vec![ColorType::White, ColorType::Underline],
+            cursor: vec![ColorType::Black, ColorType::OnWhite],
+            knob: vec![ColorType::Black, ColorType::OnBrightWhite],
+            track: vec![ColorType::BrightBlack, ColorType::OnWhite],
+            positive: vec![ColorType::Green, ColorType::Bold],
+            negative: vec![ColorType::Red],
+            mood: vec![
+                vec![ColorType::Red],
+                vec![ColorType::BrightRed],
+                vec![ColorType::Yellow],
+                vec![ColorType::BrightGreen],
+                vec![ColorType::Green],
+            ],
+        }
+    }
+}
+
+impl Theme {
+    // the theme everything is currently drawn with
+    pub fn Current () -> std::sync::Arc <Theme> {
+        CURRENT.read().clone()
+    }
+
+    pub fn Set (theme: Theme) {
+        *CURRENT.write() = std::sync::Arc::new(theme);
+    }
+
+    pub fn FromJson (text: &str) -> Result <Theme, String> {
+        serde_json::from_str(text).map_err(|error| error.to_string())
+    }
+
+    pub fn FromFile (path: &str) -> Result <Theme, String> {
+        let text = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+        Theme::FromJson(&text)
+    }
+
+    // the style for a value on the mood scale between min and max
+    pub fn GetMoodStyle (&self, value: i32, min: i32, max: i32) -> Style {
+        if self.mood.is_empty() {  return self.text.clone();  }
+        let steps = (max - min).max(0) as usize + 1;
+        let step = (value.clamp(min, max.max(min)) - min) as usize;
+        self.mood[step * self.mood.len() / steps].clone()
+    }
+}
@@ -0,0 +1,938 @@
+// Widgets for building forms. Each one keeps its own state, draws itself as spans and handles the
+// keys and mouse while it has the focus, reporting back whether anything changed
+// Every widget is drawn centered within the width it's given, and mouse positions are taken relative
+// to that same width, so what's clicked always lines up with what's drawn
+//   e.g. let mut title = TextInput::new(String::new()).Placeholder("Title");
+//        if title.HandleKeys(&keyParser, &typed) == Response::Changed { ... }
+
+use super::{Colorize, ColorType, Colored, Span, MouseAction, GetDisplayWidth};
+use super::theme::{Style, Theme};
+use crate::eventHandler::{KeyCode, KeyModifiers, KeyParser};
+use unicode_segmentation::UnicodeSegmentation;
+
+// what a widget did with some input, from least to most important
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub enum Response {
+    // it wasn't meant for the widget (a key it doesn't use, or a click that missed it)
+    Ignored,
+    // it was used, but the value didn't change (like moving the cursor)
+    Handled,
+    // the value changed
+    Changed,
+    // a button was pushed
+    Pressed,
+}
+
+impl Response {
+    // whether the widget did anything with the input
+    pub fn IsUsed (&self) -> bool {
+        *self != Response::Ignored
+    }
+
+    // the more important of two responses, for input that went to more than one place
+    fn Or (self, other: Response) -> Response {
+        std::cmp::max(self, other)
+    }
+}
+
+pub trait Widget {
+    // the lines it's drawn as, each centered within the width
+    fn Render (&self, width: usize, focused: bool) -> Vec <Span>;
+
+    // the keys pressed while it has the focus, along with anything typed
+    fn HandleKeys (&mut self, keyParser: &KeyParser, typed: &str) -> Response;
+
+    // the mouse at a line and column of what Render drew at the same width
+    fn HandleMouse (&mut self, line: usize, column: usize, action: MouseAction, width: usize) -> Response;
+
+    // the number of lines Render draws
+    fn Height (&self) -> usize;
+}
+
+// the byte index of the grapheme (what reads as a single character, even if it's several
+// code points like an accented letter or a flag) before the position
+fn PreviousGrapheme (text: &str, position: usize) -> usize {
+    text[..position].grapheme_indices(true).next_back().map_or(0, |(index, _)| index)
+}
+
+// the byte index of the grapheme after the position
+fn NextGrapheme (text: &str, position: usize) -> usize {
+    text[position..].graphemes(true).next().map_or(position, |grapheme| position + grapheme.len())
+}
+
+// the byte index of the grapheme at a column, or the end if the text is narrower than that
+fn ColumnToIndex (text: &str, column: usize) -> usize {
+    let mut used = 0;
+    for (index, grapheme) in text.grapheme_indices(true) {
+        used += GetDisplayWidth(grapheme);
+        if used > column {  return index;  }
+    }
+    text.len()
+}
+
+// the space before something of the given size to center it within the width
+fn CenterPadding (width: usize, size: usize) -> Colored {
+    " ".repeat((width / 2).saturating_sub(size / 2)).Colorizes(vec![])
+}
+
+// where something of the given size starts when centered (see CenterPadding)
+fn CenterStart (width: usize, size: usize) -> usize {
+    (width / 2).saturating_sub(size / 2)
+}
+
+// a line of text with the cursor drawn as an inverted cell over the grapheme it's on
+// (or a blank cell followed by the placeholder while there's no text)
+fn CursorTokens (text: &str, cursor: usize, textColors: &Style, placeholder: &str) -> Vec <Colored> {
+    let theme = Theme::Current();
+    if text.is_empty() {
+        return vec![
+            " ".Stylize(&theme.cursor),
+            placeholder.Stylize(&theme.muted),
+        ];
+    }
+    let (before, after) = text.split_at(std::cmp::min(cursor, text.len()));
+    let underCursor = after.graphemes(true).next().unwrap_or(" ");
+    let after = &after[std::cmp::min(underCursor.len(), after.len())..];
+    vec![
+        before.Stylize(textColors),
+        underCursor.Stylize(&theme.cursor),
+        after.Stylize(textColors),
+    ]
+}
+
+// typing, backspace/delete and left/right (plus home/end) on a single line, the cursor being a byte index
+fn EditLine (text: &mut String, cursor: &mut usize, keyParser: &KeyParser, typed: &str) -> Response {
+    let mut response = Response::Ignored;
+    *cursor = std::cmp::min(*cursor, text.len());
+    if !typed.is_empty() {
+        text.insert_str(*cursor, typed);
+        *cursor += typed.len();
+        response = Response::Changed;
+    }
+    if keyParser.ContainsKeyCode(KeyCode::Delete) && *cursor > 0 {
+        let start = PreviousGrapheme(text, *cursor);
+        text.replace_range(start..*cursor, "");
+        *cursor = start;
+        response = Response::Changed;
+    }
+    if keyParser.ContainsKeyCode(KeyCode::ForwardDelete) && *cursor < text.len() {
+        let end = NextGrapheme(text, *cursor);
+        text.replace_range(*cursor..end, "");
+        response = Response::Changed;
+    }
+    let moved = [KeyCode::Left, KeyCode::Right, KeyCode::Home, KeyCode::End].into_iter()
+        .any(|key| keyParser.ContainsKeyCode(key));
+    if keyParser.ContainsKeyCode(KeyCode::Left) {
+        *cursor = PreviousGrapheme(text, *cursor);
+    }
+    if keyParser.ContainsKeyCode(KeyCode::Right) {
+        *cursor = NextGrapheme(text, *cursor);
+    }
+    if keyParser.ContainsKeyCode(KeyCode::Home) {
+        *cursor = 0;
+    }
+    if keyParser.ContainsKeyCode(KeyCode::End) {
+        *cursor = text.len();
+    }
+    match moved {
+        true => response.Or(Response::Handled),
+        false => response,
+    }
+}
+
+
+// A single line of text
+// The cursor is a byte index into the text and always sits on a grapheme boundary
+#[derive(Clone, Debug, Default)]
+pub struct TextInput {
+    text: String,
+    cursor: usize,
+    placeholder: String,
+    // anything typed that doesn't pass is dropped (like keeping a number field to digits)
+    filter: Option <fn (char) -> bool>,
+}
+
+impl TextInput {
+    pub fn new (text: String) -> Self {
+        TextInput {
+            cursor: text.len(),
+            text,
+            placeholder: String::new(),
+            filter: None,
+        }
+    }
+
+    // the text shown in place of the text while it's empty
+    pub fn Placeholder (mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    pub fn Filter (mut self, filter: fn (char) -> bool) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn GetText (&self) -> &str {
+        &self.text
+    }
+
+    // replaces the text, leaving the cursor at the end
+    pub fn SetText (&mut self, text: String) {
+        self.cursor = text.len();
+        self.text = text;
+    }
+
+    fn GetTokens (&self, focused: bool) -> Vec <Colored> {
+        let theme = Theme::Current();
+        match (focused, self.text.is_empty()) {
+            (true, _) => CursorTokens(&self.text, self.cursor, &theme.active, &self.placeholder),
+            (false, true) => vec![self.placeholder.Stylize(&theme.text)],
+            (false, false) => vec![self.text.Stylize(&theme.text)],
+        }
+    }
+}
+
+impl Widget for TextInput {
+    fn Render (&self, width: usize, focused: bool) -> Vec <Span> {
+        let tokens = self.GetTokens(focused);
+        let size = tokens.iter().map(|token| token.GetSize()).sum();
+        vec![Span::FromTokens([vec![CenterPadding(width, size)], tokens].concat())]
+    }
+
+    fn HandleKeys (&mut self, keyParser: &KeyParser, typed: &str) -> Response {
+        let typed: String = match self.filter {
+            Some(filter) => typed.chars().filter(|chr| filter(*chr)).collect(),
+            None => typed.to_string(),
+        };
+        EditLine(&mut self.text, &mut self.cursor, keyParser, &typed)
+    }
+
+    // clicking places the cursor; a little to either side of the text still counts, since it's a small target
+    fn HandleMouse (&mut self, line: usize, column: usize, action: MouseAction, width: usize) -> Response {
+        if line != 0 || action != MouseAction::Click {  return Response::Ignored;  }
+        let size = self.GetTokens(false).iter().map(|token| token.GetSize()).sum();
+        let start = CenterStart(width, size);
+        if column + 5 < start || column >= start + size + 5 {  return Response::Ignored;  }
+        self.cursor = ColumnToIndex(&self.text, column.saturating_sub(start));
+        Response::Handled
+    }
+
+    fn Height (&self) -> usize {
+        1
+    }
+}
+
+
+// A multi-line box of text, wrapped to its width
+// The cursor is a byte index into the text and always sits on a grapheme boundary
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct TextArea {
+    text: String,
+    cursor: usize,
+    placeholder: String,
+    // the size of the box (width, height), which can be smaller than the width it's centered in
+    size: (usize, usize),
+    // the first visible (wrapped) line
+    scrolled: usize,
+}
+
+impl TextArea {
+    pub fn new (text: String, size: (usize, usize)) -> Self {
+        TextArea {
+            cursor: text.len(),
+            text,
+            placeholder: String::new(),
+            size,
+            scrolled: 0,
+        }
+    }
+
+    pub fn Placeholder (mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    pub fn GetText (&self) -> &str {
+        &self.text
+    }
+
+    pub fn SetText (&mut self, text: String) {
+        self.cursor = text.len();
+        self.text = text;
+    }
+
+    pub fn Resize (&mut self, size: (usize, usize)) {
+        self.size = (std::cmp::max(size.0, 1), std::cmp::max(size.1, 1));
+    }
+
+    // Splits the text into the lines it's shown as, each a byte range into the text.
+    // Lines take up at most width - 1 cells so the cursor always has a cell at the end.
+    // A line broken between words keeps the space it was broken at, newlines aren't part of any line.
+    pub fn GetLines (&self) -> Vec <std::ops::Range <usize>> {
+        let width = std::cmp::max(self.size.0.saturating_sub(1), 1);
+        let mut lines = vec![];
+        let mut paragraphStart = 0;
+        for paragraph in self.text.split('\n') {
+            let end = paragraphStart + paragraph.len();
+            let graphemes: Vec <(usize, &str)> = paragraph.grapheme_indices(true)
+                .map(|(index, grapheme)| (paragraphStart + index, grapheme))
+                .collect();
+            let byte = |index: usize| graphemes.get(index).map_or(end, |(byte, _)| *byte);
+            let mut start = 0;
+            loop {
+                // the first grapheme that doesn't fit on the line
+                let mut limit = start;
+                let mut used = 0;
+                while limit < graphemes.len() && used + GetDisplayWidth(graphemes[limit].1) <= width {
+                    used += GetDisplayWidth(graphemes[limit].1);
+                    limit += 1;
+                }
+                if limit == graphemes.len() {  break;  }
+                let lineEnd = match (start + 1..limit).rev().find(|index| graphemes[*index].1 == " ") {
+                    Some(space) => space + 1,
+                    None => std::cmp::max(limit, start + 1),  // a single word (or grapheme) wider than the box
+                };
+                lines.push(byte(start)..byte(lineEnd));
+                start = lineEnd;
+            }
+            lines.push(byte(start)..end);
+            paragraphStart = end + 1;
+        }
+        lines
+    }
+
+    fn GetCursorLine (&self, lines: &[std::ops::Range <usize>]) -> usize {
+        lines.iter().rposition(|line| line.start <= self.cursor).unwrap_or(0)
+    }
+
+    // where the cursor can go at the end of a line; on a wrapped line that's before the
+    // character it was broken at, since after it is the start of the next line
+    fn GetLineEnd (&self, lines: &[std::ops::Range <usize>], index: usize) -> usize {
+        let line = &lines[index];
+        match lines.get(index + 1).is_some_and(|next| next.start == line.end) {
+            true => std::cmp::max(PreviousGrapheme(&self.text, line.end), line.start),
+            false => line.end,
+        }
+    }
+
+    // the start of the word before the cursor (skipping any whitespace first)
+    fn PreviousWord (&self) -> usize {
+        let before = self.text[..self.cursor].trim_end();
+        before.char_indices().rev()
+            .find(|(_, chr)| chr.is_whitespace())
+            .map_or(0, |(index, chr)| index + chr.len_utf8())
+    }
+
+    // the end of the word after the cursor (skipping any whitespace first)
+    fn NextWord (&self) -> usize {
+        let after = &self.text[self.cursor..];
+        let word = after.trim_start();
+        let skipped = after.len() - word.len();
+        self.cursor + skipped + word.find(char::is_whitespace).unwrap_or(word.len())
+    }
+
+    // moves the cursor up or down a line, keeping the same column where possible
+    fn MoveVertically (&mut self, lines: &[std::ops::Range <usize>], down: bool) {
+        let current = self.GetCursorLine(lines);
+        let target = match down {
+            true if current + 1 < lines.len() => current + 1,
+            false if current > 0 => current - 1,
+            // already on the first/last line, so it goes to the start/end instead
+            true => {  self.cursor = self.text.len();  return;  },
+            false => {  self.cursor = 0;  return;  },
+        };
+        let column = GetDisplayWidth(&self.text[lines[current].start..self.cursor]);
+        let start = lines[target].start;
+        let end = self.GetLineEnd(lines, target);
+        self.cursor = start + ColumnToIndex(&self.text[start..end], column);
+    }
+
+    fn Insert (&mut self, text: &str) {
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+
+    fn ScrollToCursor (&mut self) {
+        let line = self.GetCursorLine(&self.GetLines());
+        if line < self.scrolled {
+            self.scrolled = line;
+        } else if line >= self.scrolled + self.size.1 {
+            self.scrolled = line + 1 - self.size.1;
+        }
+    }
+}
+
+impl Widget for TextArea {
+    // The visible lines, each padded out to the full width of the box so it lines up when centered.
+    // The cursor is drawn as an inverted cell while focused, and the placeholder shows while empty.
+    fn Render (&self, width: usize, focused: bool) -> Vec <Span> {
+        let (boxWidth, height) = self.size;
+        let theme = Theme::Current();
+        let mut render = vec![];
+        if self.text.is_empty() && !focused {
+            let placeholder = &self.placeholder[..ColumnToIndex(&self.placeholder, boxWidth)];
+            let padding = boxWidth - GetDisplayWidth(placeholder);
+            render.push(vec![
+                format!("{}{}{}", " ".repeat(padding / 2), placeholder, " ".repeat(padding - padding / 2)).Stylize(&theme.text),
+            ]);
+        } else {
+            let lines = self.GetLines();
+            let cursorLine = self.GetCursorLine(&lines);
+            let scrolled = std::cmp::min(self.scrolled, lines.len().saturating_sub(height));
+            for (index, line) in lines.iter().enumerate().skip(scrolled).take(height) {
+                let text = &self.text[line.clone()];
+                let size = GetDisplayWidth(text);
+                if !focused || index != cursorLine {
+                    render.push(vec![
+                        text.Stylize(&theme.text),
+                        " ".repeat(boxWidth.saturating_sub(size)).Colorizes(vec![]),
+                    ]);
+                    continue;
+                }
+                // splitting the line around the cursor, which covers the whole grapheme under it
+                let (before, after) = text.split_at(self.cursor - line.start);
+                let underCursor = after.graphemes(true).next().unwrap_or(" ");
+                let after = &after[std::cmp::min(underCursor.len(), after.len())..];
+                let size = GetDisplayWidth(before) + GetDisplayWidth(underCursor) + GetDisplayWidth(after);
+                render.push(vec![
+                    before.Stylize(&theme.text),
+                    underCursor.Stylize(&theme.cursor),
+                    after.Stylize(&theme.text),
+                    " ".repeat(boxWidth.saturating_sub(size)).Colorizes(vec![]),
+                ]);
+            }
+        }
+        while render.len() < height {
+            render.push(vec![" ".repeat(boxWidth).Colorizes(vec![])]);
+        }
+        render.into_iter()
+            .map(|tokens| Span::FromTokens([vec![CenterPadding(width, boxWidth)], tokens].concat()))
+            .collect()
+    }
+
+    // Return adds a new line, Option makes the arrows/Delete/forward delete move by words, and
+    // Command or Control makes left/right go to the start/end of the line (as do Home and End)
+    fn HandleKeys (&mut self, keyParser: &KeyParser, typed: &str) -> Response {
+        let byWord = keyParser.ContainsModifier(&KeyModifiers::Option);
+        let byLine = keyParser.ContainsModifier(&KeyModifiers::Command) || keyParser.ContainsModifier(&KeyModifiers::Control);
+        let original = (self.text.len(), self.cursor);
+
+        self.Insert(typed);
+        if keyParser.ContainsKeyCode(KeyCode::Return) {
+            self.Insert("\n");
+        }
+        if keyParser.ContainsKeyCode(KeyCode::Delete) {
+            let start = match byWord {
+                true => self.PreviousWord(),
+                false => PreviousGrapheme(&self.text, self.cursor),
+            };
+            self.text.replace_range(start..self.cursor, "");
+            self.cursor = start;
+        }
+        if keyParser.ContainsKeyCode(KeyCode::ForwardDelete) {
+            let end = match byWord {
+                true => self.NextWord(),
+                false => NextGrapheme(&self.text, self.cursor),
+            };
+            self.text.replace_range(self.cursor..end, "");
+        }
+        let changed = self.text.len() != original.0 || !typed.is_empty();
+
+        let lines = self.GetLines();
+        let line = self.GetCursorLine(&lines);
+        if keyParser.ContainsKeyCode(KeyCode::Home) || (byLine && keyParser.ContainsKeyCode(KeyCode::Left)) {
+            self.cursor = lines[line].start;
+        } else if keyParser.ContainsKeyCode(KeyCode::End) || (byLine && keyParser.ContainsKeyCode(KeyCode::Right)) {
+            self.cursor = self.GetLineEnd(&lines, line);
+        } else if keyParser.ContainsKeyCode(KeyCode::Left) {
+            self.cursor = match byWord {
+                true => self.PreviousWord(),
+                false => PreviousGrapheme(&self.text, self.cursor),
+            };
+        } else if keyParser.ContainsKeyCode(KeyCode::Right) {
+            self.cursor = match byWord {
+                true => self.NextWord(),
+                false => NextGrapheme(&self.text, self.cursor),
+            };
+        }
+        if keyParser.ContainsKeyCode(KeyCode::Up) {
+            self.MoveVertically(&lines, false);
+        }
+        if keyParser.ContainsKeyCode(KeyCode::Down) {
+            self.MoveVertically(&lines, true);
+        }
+        self.ScrollToCursor();
+
+        match (changed, self.cursor != original.1) {
+            (true, _) => Response::Changed,
+            (false, true) => Response::Handled,
+            (false, false) => Response::Ignored,
+        }
+    }
+
+    // clicking places the cursor at a line and column within the visible part of the box
+    fn HandleMouse (&mut self, line: usize, column: usize, action: MouseAction, width: usize) -> Response {
+        let start = CenterStart(width, self.size.0);
+        if action != MouseAction::Click || line >= self.size.1 || column < start || column >= start + self.size.0 {
+            return Response::Ignored;
+        }
+        let lines = self.GetLines();
+        let index = std::cmp::min(self.scrolled + line, lines.len() - 1);
+        let lineStart = lines[index].start;
+        let end = self.GetLineEnd(&lines, index);
+        self.cursor = lineStart + ColumnToIndex(&self.text[lineStart..end], column - start);
+        Response::Handled
+    }
+
+    fn Height (&self) -> usize {
+        self.size.1
+    }
+}
+
+
+// A bar for picking a whole number within a range, with the knob showing the current value
+// Left and right step it by one, and clicking (or dragging) along the bar jumps it there
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Slider {
+    value: i32,
+    min: i32,
+    max: i32,
+    // the length of the bar (in cells)
+    width: usize,
+}
+
+impl Slider {
+    // the length of the bar
+    pub const DEFAULT_WIDTH: usize = 20;
+
+    pub fn new (value: i32, min: i32, max: i32) -> Self {
+        let max = std::cmp::max(max, min);
+        Slider {
+            value: value.clamp(min, max),
+            min,
+            max,
+            width: Slider::DEFAULT_WIDTH,
+        }
+    }
+
+    pub fn GetValue (&self) -> i32 {
+        self.value
+    }
+
+    // the value is kept within the range
+    pub fn SetValue (&mut self, value: i32) {
+        self.value = value.clamp(self.min, self.max);
+    }
+
+    fn Step (&mut self, step: i32) -> Response {
+        let previous = self.value;
+        self.SetValue(self.value + step);
+        match self.value == previous {
+            true => Response::Handled,
+            false => Response::Changed,
+        }
+    }
+}
+
+impl Widget for Slider {
+    // slides from left to right, with the knob and track colored by the theme
+    fn Render (&self, width: usize, focused: bool) -> Vec <Span> {
+        let knob = format!("{:=>2}", self.value);
+        let track = self.width.saturating_sub(knob.len());
+        let position = match self.max > self.min {
+            true => (self.value - self.min) as usize * track / (self.max - self.min) as usize,
+            false => 0,
+        }.min(track);
+        let theme = Theme::Current();
+        let mut knobColors = theme.knob.clone();
+        if focused {
+            knobColors.push(ColorType::Underline);
+        }
+        vec![Span::FromTokens(vec![
+            CenterPadding(width, self.width),
+            "=".repeat(position).Stylize(&theme.track),
+            knob.Colorizes(knobColors),
+            "=".repeat(track - position).Stylize(&theme.track),
+        ])]
+    }
+
+    fn HandleKeys (&mut self, keyParser: &KeyParser, _typed: &str) -> Response {
+        let mut response = Response::Ignored;
+        if keyParser.ContainsKeyCode(KeyCode::Left) {
+            response = response.Or(self.Step(-1));
+        }
+        if keyParser.ContainsKeyCode(KeyCode::Right) {
+            response = response.Or(self.Step(1));
+        }
+        response
+    }
+
+    // converts the column along the bar (0 through its width) into a value within the range
+    fn HandleMouse (&mut self, line: usize, column: usize, action: MouseAction, width: usize) -> Response {
+        let start = CenterStart(width, self.width);
+        if line != 0 || column < start || column > start + self.width {  return Response::Ignored;  }
+        if !matches!(action, MouseAction::Press | MouseAction::Drag | MouseAction::Click) {  return Response::Ignored;  }
+        let steps = self.max - self.min + 1;
+        let value = self.min + (column - start) as i32 * steps / std::cmp::max(self.width, 1) as i32;
+        let previous = self.value;
+        self.SetValue(value);
+        match self.value == previous {
+            true => Response::Handled,
+            false => Response::Changed,
+        }
+    }
+
+    fn Height (&self) -> usize {
+        1
+    }
+}
+
+
+// An editable list of single line items, each centered on its own line with a [x] to remove it
+// Items left empty are dropped once they lose focus, so there's never a blank one kept
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct ListInput {
+    items: Vec <String>,
+    placeholder: String,
+    // the item being edited, and the byte index of the cursor within it
+    selected: Option <usize>,
+    cursor: usize,
+    // the item being dragged with the mouse, if any, and whether it's been moved yet
+    dragging: Option <usize>,
+    dragged: bool,
+}
+
+impl ListInput {
+    pub fn new (items: Vec <String>) -> Self {
+        ListInput {
+            items,
+            ..Default::default()
+        }
+    }
+
+    pub fn Placeholder (mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    pub fn GetItems (&self) -> &[String] {
+        &self.items
+    }
+
+    pub fn Len (&self) -> usize {
+        self.items.len()
+    }
+
+    // the item being edited, if any
+    pub fn GetSelected (&self) -> Option <usize> {
+        self.selected
+    }
+
+    // swaps out the text of the item being edited (taking a suggestion), leaving the cursor at the end
+    pub fn ReplaceSelected (&mut self, text: &str) {
+        let Some(index) = self.selected else {  return;  };
+        self.items[index] = text.to_string();
+        self.cursor = self.items[index].len();
+    }
+
+    // moves the focus to another item (or none), dropping the one left if it's empty
+    fn Focus (&mut self, next: Option <usize>) {
+        let mut next = next.filter(|index| *index < self.items.len());
+        if let Some(current) = self.selected.take() && Some(current) != next && self.items[current].is_empty() {
+            self.items.remove(current);
+            next = next.map(|index| if index > current { index - 1 } else { index });
+        }
+        self.selected = next;
+        self.cursor = next.map_or(0, |index| self.items[index].len());
+    }
+
+    // starts editing an item (with the cursor at its end)
+    pub fn Select (&mut self, index: usize) {
+        self.Focus(Some(index));
+    }
+
+    // adds a blank item to the end and starts editing it
+    pub fn Add (&mut self) {
+        self.Focus(None);
+        self.items.push(String::new());
+        self.Focus(Some(self.items.len() - 1));
+    }
+
+    pub fn Deselect (&mut self) {
+        self.Focus(None);
+    }
+
+    // removes an item, moving the focus to the one before it if it was being edited
+    pub fn Remove (&mut self, index: usize) {
+        if index >= self.items.len() {  return;  }
+        let selected = self.selected.take();
+        self.items.remove(index);
+        self.selected = match selected {
+            Some(current) if current == index => index.checked_sub(1).or((!self.items.is_empty()).then_some(0)),
+            Some(current) if current > index => Some(current - 1),
+            other => other,
+        };
+        self.cursor = self.selected.map_or(0, |index| self.items[index].len());
+    }
+
+    // moves an item to another place in the list, keeping it focused if it was
+    fn MoveItem (&mut self, from: usize, to: usize) -> Response {
+        if from >= self.items.len() || to >= self.items.len() || from == to {  return Response::Handled;  }
+        let item = self.items.remove(from);
+        self.items.insert(to, item);
+        if self.selected == Some(from) {
+            self.selected = Some(to);
+        } else if let Some(current) = self.selected {
+            // the items between the two shift over by one to fill the gap
+            if from < current && current <= to {
+                self.selected = Some(current - 1);
+            } else if to <= current && current < from {
+                self.selected = Some(current + 1);
+            }
+        }
+        Response::Changed
+    }
+
+    // The tokens for an item, followed by the button that removes it.
+    // The item being edited is underlined with the cursor drawn as an inverted cell.
+    fn GetItemTokens (&self, index: usize, focused: bool) -> Vec <Colored> {
+        let item = &self.items[index];
+        let theme = Theme::Current();
+        let mut tokens = match focused && self.selected == Some(index) {
+            true => CursorTokens(item, self.cursor, &theme.active, &self.placeholder),
+            false if item.is_empty() => vec![self.placeholder.Stylize(&theme.text)],
+            false => vec![item.Stylize(&theme.text)],
+        };
+        tokens.push(" [x]".Stylize(&theme.muted));
+        tokens
+    }
+
+    // the width of an item as rendered (while being edited), remove button and all
+    pub fn GetItemWidth (&self, index: usize) -> usize {
+        self.GetItemTokens(index, true).iter().map(|token| token.GetSize()).sum()
+    }
+
+    // where an item starts when centered within the width
+    pub fn GetItemStart (&self, index: usize, width: usize) -> usize {
+        CenterStart(width, self.GetItemWidth(index))
+    }
+
+    // a click at a column along an item (from where it starts) either removes it or places the cursor
+    // clicks well to the left of the item, or past its end, miss it
+    fn Click (&mut self, index: usize, column: usize, width: usize) -> Response {
+        let start = self.GetItemStart(index, width);
+        let size = self.GetItemWidth(index);
+        if column + 5 < start || column >= start + size {  return Response::Ignored;  }
+        let column = column.saturating_sub(start);
+        if column + 3 >= size {
+            self.Remove(index);
+            return Response::Changed;
+        }
+        self.Focus(Some(index));
+        if let Some(index) = self.selected {
+            self.cursor = ColumnToIndex(&self.items[index], column);
+        }
+        Response::Handled
+    }
+
+    // pressing on an item picks it up, and holding it over another moves it there
+    fn StartDrag (&mut self, index: usize) {
+        self.dragging = Some(index);
+        self.dragged = false;
+    }
+
+    fn DragTo (&mut self, index: usize) -> Response {
+        let Some(from) = self.dragging else {  return Response::Ignored;  };
+        if index == from {  return Response::Handled;  }
+        self.dragging = Some(index);
+        self.dragged = true;
+        self.MoveItem(from, index)
+    }
+
+    // Lets go of whatever's being dragged
+    // Returns whether the item was actually moved (rather than just clicked), as the
+    // click that ends a drag shouldn't also count as a click on wherever it was dropped
+    pub fn EndDrag (&mut self) -> bool {
+        self.dragging = None;
+        std::mem::take(&mut self.dragged)
+    }
+}
+
+impl Widget for ListInput {
+    fn Render (&self, width: usize, focused: bool) -> Vec <Span> {
+        (0..self.items.len()).map(|index| {
+            let tokens = self.GetItemTokens(index, focused);
+            let size = tokens.iter().map(|token| token.GetSize()).sum();
+            Span::FromTokens([vec![CenterPadding(width, size)], tokens].concat())
+        }).collect()
+    }
+
+    // Typing, Delete and left/right edit the focused item, up/down move between items,
+    // Option + up/down moves the item itself and Command/Control + Delete (or Delete on an empty item) removes it
+    fn HandleKeys (&mut self, keyParser: &KeyParser, typed: &str) -> Response {
+        let Some(index) = self.selected else {  return Response::Ignored;  };
+        let delete = keyParser.ContainsKeyCode(KeyCode::Delete);
+        if keyParser.ContainsModifier(&KeyModifiers::Option) {
+            if keyParser.ContainsKeyCode(KeyCode::Up) {
+                return self.MoveItem(index, index.saturating_sub(1));
+            }
+            if keyParser.ContainsKeyCode(KeyCode::Down) {
+                return self.MoveItem(index, index + 1);
+            }
+            return Response::Ignored;
+        }
+        let removing = keyParser.ContainsModifier(&KeyModifiers::Command) || keyParser.ContainsModifier(&KeyModifiers::Control);
+        if delete && (removing || self.items[index].is_empty()) {
+            self.Remove(index);
+            return Response::Changed;
+        }
+        if keyParser.ContainsKeyCode(KeyCode::Up) && index > 0 {
+            self.Focus(Some(index - 1));
+            return Response::Handled;
+        }
+        if keyParser.ContainsKeyCode(KeyCode::Down) && index + 1 < self.items.len() {
+            self.Focus(Some(index + 1));
+            return Response::Handled;
+        }
+        EditLine(&mut self.items[index], &mut self.cursor, keyParser, typed)
+    }
+
+    // each line is an item; pressing picks it up to be dragged, and clicking edits or removes it
+    fn HandleMouse (&mut self, line: usize, column: usize, action: MouseAction, width: usize) -> Response {
+        if line >= self.items.len() {  return Response::Ignored;  }
+        match action {
+            MouseAction::Press => {
+                self.StartDrag(line);
+                Response::Handled
+            },
+            MouseAction::Drag => self.DragTo(line),
+            MouseAction::Click => self.Click(line, column, width),
+            _ => Response::Ignored,
+        }
+    }
+
+    fn Height (&self) -> usize {
+        self.items.len()
+    }
+}
+
+
+// A boxed label that's pushed by clicking it, or with Return while it has the focus
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Button {
+    label: String,
+}
+
+impl Button {
+    // the narrowest the inside of the box gets, so short labels still make a decent target
+    const MIN_WIDTH: usize = 8;
+
+    pub fn new (label: &str) -> Self {
+        Button {
+            label: label.to_string(),
+        }
+    }
+
+    // the width of the box, border included
+    pub fn GetWidth (&self) -> usize {
+        std::cmp::max(GetDisplayWidth(&self.label), Button::MIN_WIDTH) + 2
+    }
+}
+
+impl Widget for Button {
+    fn Render (&self, width: usize, focused: bool) -> Vec <Span> {
+        let inner = self.GetWidth() - 2;
+        let padding = inner - GetDisplayWidth(&self.label);
+        let lines = [
+            format!("┌{}┐", "─".repeat(inner)),
+            format!("│{}{}{}│", " ".repeat(padding / 2), self.label, " ".repeat(padding - padding / 2)),
+            format!("└{}┘", "─".repeat(inner)),
+        ];
+        let colors = match focused {
+            true => Theme::Current().highlight.clone(),
+            false => vec![],
+        };
+        lines.into_iter()
+            .map(|line| Span::FromTokens(vec![CenterPadding(width, self.GetWidth()), line.Colorizes(colors.clone())]))
+            .collect()
+    }
+
+    fn HandleKeys (&mut self, keyParser: &KeyParser, _typed: &str) -> Response {
+        match keyParser.ContainsKeyCode(KeyCode::Return) {
+            true => Response::Pressed,
+            false => Response::Ignored,
+        }
+    }
+
+    fn HandleMouse (&mut self, line: usize, column: usize, action: MouseAction, width: usize) -> Response {
+        let start = CenterStart(width, self.GetWidth());
+        match action == MouseAction::Click && line < 3 && column >= start && column < start + self.GetWidth() {
+            true => Response::Pressed,
+            false => Response::Ignored,
+        }
+    }
+
+    fn Height (&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a parser given the bytes as a single read from stdin
+    fn Parse (bytes: &[u8]) -> KeyParser {
+        let mut keyParser = KeyParser::new();
+        keyParser.ParseBytes(&mut vte::Parser::new(), bytes);
+        keyParser
+    }
+
+    #[test]
+    fn DeletesTheWordBeforeWithAltBackspace () {
+        // ^[ + DEL from most terminals, ^[ + ^H from some
+        for bytes in [b"\x1B\x7F", b"\x1B\x08"] {
+            let mut textArea = TextArea::new(String::from("one two"), (20, 3));
+            assert_eq!(textArea.HandleKeys(&Parse(bytes), ""), Response::Changed);
+            assert_eq!(textArea.GetText(), "one ");
+        }
+    }
+
+    #[test]
+    fn DeletesTheWordAfterWithAltForwardDelete () {
+        let mut textArea = TextArea::new(String::from("one two"), (20, 3));
+        textArea.HandleKeys(&Parse(b"\x1B[H"), "");
+        assert_eq!(textArea.HandleKeys(&Parse(b"\x1B[3;3~"), ""), Response::Changed);
+        assert_eq!(textArea.GetText(), " two");
+    }
+
+    #[test]
+    fn MovesToTheEdgesOfTheLine () {
+        // control + left/right from xterm, and command (super) + left/right from the kitty protocol
+        for (start, end) in [(&b"\x1B[1;5D"[..], &b"\x1B[1;5C"[..]), (b"\x1B[1;9D", b"\x1B[1;9C")] {
+            let mut textArea = TextArea::new(String::from("one two"), (20, 3));
+            assert_eq!(textArea.HandleKeys(&Parse(start), ""), Response::Handled);
+            textArea.HandleKeys(&Parse(b""), "<");
+            assert_eq!(textArea.HandleKeys(&Parse(end), ""), Response::Handled);
+            textArea.HandleKeys(&Parse(b""), ">");
+            assert_eq!(textArea.GetText(), "<one two>");
+        }
+    }
+
+    #[test]
+    fn RemovesAnItemWithControlBackspace () {
+        // control + backspace (^H), and command (super) + backspace from the kitty protocol
+        for bytes in [&b"\x08"[..], b"\x1B[127;9u"] {
+            let mut list = ListInput::new(vec![String::from("eggs"), String::from("toast")]);
+            list.Select(0);
+            assert_eq!(list.HandleKeys(&Parse(bytes), ""), Response::Changed);
+            assert_eq!(list.GetItems(), ["toast"]);
+        }
+        // backspace on its own only deletes a character
+        let mut list = ListInput::new(vec![String::from("eggs")]);
+        list.Select(0);
+        list.HandleKeys(&Parse(b"\x7F"), "");
+        assert_eq!(list.GetItems(), ["egg"]);
+    }
+}
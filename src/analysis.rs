@@ -0,0 +1,114 @@
+use std::collections::{BTreeMap, BTreeSet};
+use crate::entries::Logs;
+
+// below this the spread of a group is treated as noise, otherwise a handful of identical
+// moods would give a near infinite effect size
+const MIN_DEVIATION: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Food,
+    Event,
+}
+
+// how the average mood differs between days with and without an item
+#[derive(Debug, Clone)]
+pub struct Correlation {
+    pub item: String,
+    pub kind: ItemKind,
+    pub with_mean: f64,
+    pub with_count: usize,
+    pub without_mean: f64,
+    pub without_count: usize,
+    // the difference in means over the pooled standard deviation (Cohen's d)
+    pub effect_size: f64,
+}
+
+// everything known about a single day, merged across all of that day's entries
+#[derive(Default)]
+struct Day {
+    moods: Vec<f64>,
+    // (lowercase name, is food) -> name as first written
+    items: BTreeMap<(String, bool), String>,
+}
+
+impl Day {
+    fn get_mood(&self) -> Option<f64> {
+        if self.moods.is_empty() {  return None;  }
+        Some(self.moods.iter().sum::<f64>() / self.moods.len() as f64)
+    }
+}
+
+fn mean_and_variance(values: &[f64]) -> (f64, f64) {
+    let mean = values.iter().sum::<f64>() / values.len().max(1) as f64;
+    let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / (values.len().max(2) - 1) as f64;
+    (mean, variance)
+}
+
+// Compares the mood on days each food/event appears against the days it doesn't.
+// With next_day set, an item is compared against the following day's mood instead.
+// Items seen on fewer than min_samples days (or missing from fewer) are left out.
+// The results are ranked by the size of the effect, strongest first.
+pub fn analyze(logs: &Logs, next_day: bool, min_samples: usize) -> Vec<Correlation> {
+    let mut days: BTreeMap<chrono::NaiveDate, Day> = BTreeMap::new();
+    for log in logs.iter() {
+        let Some(date) = log.get_naive_date() else {  continue;  };
+        let day = days.entry(date).or_default();
+        if let Some(mood) = &log.mood {
+            day.moods.push(mood.quality as f64);
+        }
+        let events = log.events.iter().flatten().map(|item| (item, false));
+        let foods = log.food.iter().flatten().map(|item| (item, true));
+        for (item, is_food) in events.chain(foods) {
+            let trimmed = item.trim();
+            if trimmed.is_empty() {  continue;  }
+            day.items.entry((trimmed.to_lowercase(), is_food)).or_insert_with(|| trimmed.to_string());
+        }
+    }
+
+    // each mood paired with the day the items are taken from
+    let lag = chrono::Days::new(next_day as u64);
+    let samples: Vec<(f64, &Day)> = days.iter().filter_map(|(date, day)| {
+        let mood = day.get_mood()?;
+        // if nothing was written the day before, there's no telling what was or wasn't eaten
+        let source = days.get(&(*date - lag))?;
+        Some((mood, source))
+    }).collect();
+
+    let mut items = BTreeSet::new();
+    for (_, day) in &samples {
+        for (key, name) in &day.items {
+            items.insert((key.clone(), name.clone()));
+        }
+    }
+
+    let mut correlations = vec![];
+    let mut seen = BTreeSet::new();
+    for (key, name) in items {
+        if !seen.insert(key.clone()) {  continue;  }  // the same item written with different casing
+        let (with, without): (Vec<_>, Vec<_>) = samples.iter().partition(|(_, day)| day.items.contains_key(&key));
+        if with.len() < min_samples.max(1) || without.len() < min_samples.max(1) {  continue;  }
+
+        let with: Vec<f64> = with.iter().map(|(mood, _)| *mood).collect();
+        let without: Vec<f64> = without.iter().map(|(mood, _)| *mood).collect();
+        let (with_mean, with_variance) = mean_and_variance(&with);
+        let (without_mean, without_variance) = mean_and_variance(&without);
+        let pooled = (
+            ((with.len() - 1) as f64 * with_variance + (without.len() - 1) as f64 * without_variance) /
+            (with.len() + without.len()).saturating_sub(2).max(1) as f64
+        ).sqrt();
+
+        correlations.push(Correlation {
+            item: name,
+            kind: if key.1 { ItemKind::Food } else { ItemKind::Event },
+            with_mean,
+            with_count: with.len(),
+            without_mean,
+            without_count: without.len(),
+            effect_size: (with_mean - without_mean) / pooled.max(MIN_DEVIATION),
+        });
+    }
+
+    correlations.sort_by(|a, b| b.effect_size.abs().total_cmp(&a.effect_size.abs()));
+    correlations
+}
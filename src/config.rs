@@ -0,0 +1,123 @@
+// user settings, read from config.json (next to logs.json)
+// every field has a default so a missing or partial file still loads
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Config {
+    pub mood_dimensions: Vec<MoodDimension>,
+    pub habits: Vec<HabitDefinition>,
+    pub custom_fields: Vec<CustomField>,
+    // a locale code like "es", otherwise it's taken from LANG
+    pub locale: Option<String>,
+    // "dark", "light" or the name of a file in themes/
+    pub theme: Option<String>,
+    // the most times a second the screen is redrawn (0 for no limit), otherwise 60
+    pub max_frame_rate: Option<u32>,
+}
+
+impl Config {
+    // no file is the same as an empty one, but one that can't be read or parsed is an error rather than
+    // being swapped for the defaults (which would quietly drop the habits, custom fields...)
+    pub fn load() -> std::io::Result<Self> {
+        match std::fs::File::open("config.json") {
+            Ok(file) => {
+                let reader = std::io::BufReader::new(file);
+                serde_json::from_reader(reader).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn get_mood_dimension(&self, name: &str) -> Option<&MoodDimension> {
+        self.mood_dimensions.iter().find(|dimension| dimension.name == name)
+    }
+
+    pub fn get_custom_field(&self, name: &str) -> Option<&CustomField> {
+        self.custom_fields.iter().find(|field| field.name == name)
+    }
+}
+
+// a user defined mood scale (energy, anxiety, focus...) with its own range
+// the labels are spread evenly across the range, so there doesn't need to be one per value
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct MoodDimension {
+    pub name: String,
+    pub min: i32,
+    pub max: i32,
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+impl MoodDimension {
+    pub fn clamp(&self, value: i32) -> i32 {
+        value.clamp(self.min, self.max.max(self.min))
+    }
+
+    // the value new entries start at
+    pub fn middle(&self) -> i32 {
+        self.min + (self.max - self.min) / 2
+    }
+
+    pub fn get_label(&self, value: i32) -> Option<&String> {
+        if self.labels.is_empty() {  return None;  }
+        let steps = (self.max - self.min).max(0) as usize + 1;
+        let step = (self.clamp(value) - self.min) as usize;
+        self.labels.get(step * self.labels.len() / steps)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HabitKind {
+    // done or not done (exercise)
+    #[default] Check,
+    // done some number of times, complete once the target is reached (glasses of water)
+    Count,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct HabitDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub kind: HabitKind,
+    // only used by count habits
+    #[serde(default = "HabitDefinition::default_target")]
+    pub target: u32,
+}
+
+impl HabitDefinition {
+    fn default_target() -> u32 {  1  }
+
+    pub fn is_complete(&self, count: u32) -> bool {
+        match self.kind {
+            HabitKind::Check => count > 0,
+            HabitKind::Count => count >= self.target.max(1),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    Text,
+    Number,
+    Boolean,
+    List,
+    // 1 through max
+    Rating,
+}
+
+// an extra input on every entry (sleep hours, weather, location...)
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct CustomField {
+    pub name: String,
+    pub kind: FieldKind,
+    // only used by ratings
+    #[serde(default = "CustomField::default_max")]
+    pub max: i32,
+}
+
+impl CustomField {
+    fn default_max() -> i32 {  5  }
+}
@@ -1,17 +1,29 @@
-use chrono;
-use chrono::Datelike;
-use serde;
 use crate::TermRender;
 use crate::TermRender::{Colorize};
+use crate::config::Config;
+use crate::locale::{self, tr};
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct Logs(Vec<Log>);
 
 impl Logs {
     pub fn len(&self) -> usize { self.0.len() }
-    pub fn push(&mut self, item: Log) { self.0.push(item) }
-    pub fn new() -> Self { Self {0: vec![]} }
+    pub fn new() -> Self { Self(vec![]) }
     pub fn remove(&mut self, index: usize) -> Log { self.0.remove(index) }
+    pub fn iter(&self) -> std::slice::Iter<'_, Log> { self.0.iter() }
+    
+    // oldest first (see Log::get_local_time)
+    pub fn sort_by_date(&mut self) {
+        self.0.sort_by_key(|log| log.get_local_time());
+    }
+    
+    // inserts the log after any from the same time, returning where it went
+    pub fn insert_sorted(&mut self, log: Log) -> usize {
+        let time = log.get_local_time();
+        let index = self.0.partition_point(|other| other.get_local_time() <= time);
+        self.0.insert(index, log);
+        index
+    }
 }
 
 impl std::ops::Index<usize> for Logs {
@@ -34,59 +46,97 @@ pub struct Mood {
     pub quality: usize,
     pub description: String,
     pub reason: Option<String>,
+    pub dimensions: Option<Vec<MoodRating>>,
+}
+
+// a rating on one of the user's mood dimensions (see config::MoodDimension)
+// the name is stored rather than an index so renaming/reordering the config doesn't scramble old entries
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct MoodRating {
+    pub name: String,
+    pub value: i32,
+}
+
+// the value of one of the user's custom fields (see config::CustomField)
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldValue {
+    Text(String),
+    Number(f64),
+    Boolean(bool),
+    List(Vec<String>),
+    Rating(i32),
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct CustomValue {
+    pub name: String,
+    pub value: FieldValue,
+}
+
+impl Mood {
+    pub fn get_dimension(&self, name: &str) -> Option<i32> {
+        self.dimensions.as_ref()?.iter().find(|rating| rating.name == name).map(|rating| rating.value)
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct Log {
     pub entry_date: String,
+    // older entries only have the written out date (see get_naive_date)
+    pub entry_time: Option<chrono::DateTime<chrono::Local>>,
     pub entry_title: String,
     pub entry_text: String,
     pub events: Option<Vec<String>>,
     pub food: Option<Vec<String>>,
     pub mood: Option<Mood>,
+    pub custom_fields: Option<Vec<CustomValue>>,
 }
 
 impl Log {
     pub fn new(entry_title: String, entry_text: String) -> Self {
         let time = chrono::Local::now();
-        // time.month(), time.day(), time.year(), time.weekday()
-        let entry_date = format!("{}, the {} of {}, {}", Self::get_week_day(&time), Self::get_day(&time), Self::get_month(&time), Self::get_year(&time));
+        let entry_date = locale::get().format_date(&time);
         Self {
             entry_date,
+            entry_time: Some(time),
             entry_title,
             entry_text,
             events: None,
             food: None,
             mood: None,
+            custom_fields: None,
         }
     }
     
-    pub fn get_render_text(&self) -> Vec<TermRender::Span> {
+    pub fn get_render_text(&self, config: &Config) -> Vec<TermRender::Span> {
+        let theme = TermRender::theme::Theme::Current();
         let date_span = TermRender::Span::FromTokens(vec![
             "  - ".Colorizes(vec![]),
-            self.entry_date.Colorizes(vec![TermRender::ColorType::White, TermRender::ColorType::Bold]),
+            self.get_date().Stylize(&theme.strong),
         ]);
         let title_span = TermRender::Span::FromTokens(vec![
             " *".Colorizes(vec![]),
-            self.entry_title.Colorizes(vec![TermRender::ColorType::White, TermRender::ColorType::Italic]),
+            self.entry_title.Stylize(&theme.label),
             "*".Colorizes(vec![]),
         ]);
-        let text_span = TermRender::Span::FromTokens(vec![
-            self.entry_text.Colorizes(vec![TermRender::ColorType::White])
-        ]);
+        // one span per paragraph, the reader wraps them to fit
+        let text_spans = self.entry_text.split('\n').map(|paragraph| TermRender::Span::FromTokens(vec![
+            paragraph.Stylize(&theme.text)
+        ])).collect::<Vec<_>>();
         let mut events = vec![];
         if !self.events.as_ref().unwrap_or(&vec![]).is_empty() {
             events = vec![
                 TermRender::Span::FromTokens(vec![]),
                 TermRender::Span::FromTokens(vec![
-                    " Events:".Colorizes(vec![TermRender::ColorType::White, TermRender::ColorType::Italic])
+                    format!(" {}:", tr("events")).Stylize(&theme.label)
                 ]),
             ];
         }
         for event in self.events.as_ref().unwrap_or(&vec![]) {
             let span = TermRender::Span::FromTokens(vec![
-                "  * ".Colorizes(vec![TermRender::ColorType::White, TermRender::ColorType::Italic]),
-                event.Colorizes(vec![TermRender::ColorType::White])
+                "  * ".Stylize(&theme.label),
+                event.Stylize(&theme.text)
             ]);
             events.push(span);
         }
@@ -96,14 +146,14 @@ impl Log {
             foods = vec![
                 TermRender::Span::FromTokens(vec![]),
                 TermRender::Span::FromTokens(vec![
-                    " Food:".Colorizes(vec![TermRender::ColorType::White, TermRender::ColorType::Italic])
+                    format!(" {}:", tr("food")).Stylize(&theme.label)
                 ]),
             ];
         }
         for item in self.food.as_ref().unwrap_or(&vec![]) {
             let span = TermRender::Span::FromTokens(vec![
-                "  * ".Colorizes(vec![TermRender::ColorType::White, TermRender::ColorType::Italic]),
-                item.Colorizes(vec![TermRender::ColorType::White])
+                "  * ".Stylize(&theme.label),
+                item.Stylize(&theme.text)
             ]);
             foods.push(span);
         }
@@ -113,7 +163,7 @@ impl Log {
             mood_text = vec![
                 TermRender::Span::FromTokens(vec![]),
                 TermRender::Span::FromTokens(vec![
-                    " Mood:".Colorizes(vec![TermRender::ColorType::White, TermRender::ColorType::Italic])
+                    format!(" {}:", tr("mood")).Stylize(&theme.label)
                 ]),
             ];
             
@@ -129,31 +179,107 @@ impl Log {
             mood_text.push(
                 TermRender::Span::FromTokens(vec![
                     " * ".Colorizes(vec![]),
-                    mood_icon.Colorizes(vec![TermRender::ColorType::White]),
-                    format!(" ({}/10)", mood.quality).Colorizes(vec![TermRender::ColorType::White]),
+                    mood_icon.Stylize(&theme.text),
+                    format!(" ({}/10)", mood.quality).Stylize(&theme.GetMoodStyle(mood.quality as i32, 1, 10)),
                     " ".Colorizes(vec![]),
-                    mood.description.Colorizes(vec![TermRender::ColorType::White]),
+                    mood.description.Stylize(&theme.text),
                 ])
             );
             if let Some(reason) = &mood.reason {
                 mood_text.push(
                     TermRender::Span::FromTokens(vec![
-                        " * Reason: ".Colorizes(vec![TermRender::ColorType::White, TermRender::ColorType::Italic]),
-                        reason.Colorizes(vec![TermRender::ColorType::White]),
+                        format!(" * {}: ", tr("reason")).Stylize(&theme.label),
+                        reason.Stylize(&theme.text),
+                    ])
+                );
+            }
+            for rating in mood.dimensions.as_ref().unwrap_or(&vec![]) {
+                // the dimension may have since been removed from the config, in which case only the raw value is known
+                let (scale, style) = match config.get_mood_dimension(&rating.name) {
+                    Some(dimension) => (match dimension.get_label(rating.value) {
+                        Some(label) => format!(" ({}/{}) {}", rating.value, dimension.max, label),
+                        None => format!(" ({}/{})", rating.value, dimension.max),
+                    }, theme.GetMoodStyle(rating.value, dimension.min, dimension.max)),
+                    None => (format!(" ({})", rating.value), theme.text.clone()),
+                };
+                mood_text.push(
+                    TermRender::Span::FromTokens(vec![
+                        format!(" * {}:", rating.name).Stylize(&theme.label),
+                        scale.Stylize(&style),
                     ])
                 );
             }
         }
         
-        vec![vec![date_span, title_span, text_span], events, foods, mood_text].concat()
+        let mut custom_text = vec![];
+        if !self.custom_fields.as_ref().unwrap_or(&vec![]).is_empty() {
+            custom_text.push(TermRender::Span::FromTokens(vec![]));
+        }
+        for field in self.custom_fields.as_ref().unwrap_or(&vec![]) {
+            let name = format!(" {}: ", field.name).Stylize(&theme.label);
+            let value = match &field.value {
+                FieldValue::Text(text) => text.clone(),
+                FieldValue::Number(number) => format!("{}", number),
+                FieldValue::Boolean(true) => tr("yes").to_string(),
+                FieldValue::Boolean(false) => tr("no").to_string(),
+                FieldValue::Rating(rating) => match config.get_custom_field(&field.name) {
+                    Some(definition) => format!("{}/{}", rating, definition.max),
+                    None => format!("{}", rating),
+                },
+                FieldValue::List(items) => {
+                    custom_text.push(TermRender::Span::FromTokens(vec![name]));
+                    for item in items {
+                        custom_text.push(TermRender::Span::FromTokens(vec![
+                            "  * ".Stylize(&theme.label),
+                            item.Stylize(&theme.text)
+                        ]));
+                    }
+                    continue;
+                },
+            };
+            custom_text.push(TermRender::Span::FromTokens(vec![
+                name, value.Stylize(&theme.text),
+            ]));
+        }
+        
+        [vec![date_span, title_span], text_spans, events, foods, mood_text, custom_text].concat()
+    }
+    
+    pub fn set_time(&mut self, time: chrono::DateTime<chrono::Local>) {
+        self.entry_date = locale::get().format_date(&time);
+        self.entry_time = Some(time);
+    }
+    
+    // older entries only know the day, so they're treated as written at midnight
+    pub fn get_local_time(&self) -> Option<chrono::NaiveDateTime> {
+        match &self.entry_time {
+            Some(time) => Some(time.naive_local()),
+            None => self.get_naive_date()?.and_hms_opt(0, 0, 0),
+        }
     }
     
     pub fn get_title(&self) -> String {
         self.entry_title.clone()
     }
     
+    // written out in the current locale when the exact time is known
     pub fn get_date(&self) -> String {
-        self.entry_date.clone()
+        match &self.entry_time {
+            Some(time) => locale::get().format_date(time),
+            None => self.entry_date.clone(),
+        }
+    }
+    
+    // the day the entry was written, falling back on parsing entry_date for entries from before entry_time existed
+    pub fn get_naive_date(&self) -> Option<chrono::NaiveDate> {
+        if let Some(time) = &self.entry_time {  return Some(time.date_naive());  }
+        // "Tuesday, the 16th of September, 2025"
+        let (_, date) = self.entry_date.split_once(", the ")?;
+        let (day, date) = date.split_once(" of ")?;
+        let (month, year) = date.split_once(", ")?;
+        let day = day.trim_end_matches(|chr: char| chr.is_alphabetic()).parse().ok()?;
+        let month = locale::english().parse_month(month)?;
+        chrono::NaiveDate::from_ymd_opt(year.trim().parse().ok()?, month, day)
     }
     
     pub fn add_event(&mut self, event: String) {
@@ -167,48 +293,5 @@ impl Log {
         let food = self.food.as_mut().unwrap();
         food.push(item);
     }
-    
-    fn get_week_day(time: &chrono::prelude::DateTime<chrono::Local>) -> String {
-        String::from(match time.weekday() {
-            chrono::Weekday::Sun => "Sunday",
-            chrono::Weekday::Mon => "Monday",
-            chrono::Weekday::Tue => "Tuesday",
-            chrono::Weekday::Wed => "Wednesday",
-            chrono::Weekday::Thu => "Thursday",
-            chrono::Weekday::Fri => "Friday",
-            chrono::Weekday::Sat => "Saturday",
-        })
-    }
-    
-    fn get_day(time: &chrono::prelude::DateTime<chrono::Local>) -> String {
-        let day = time.day();
-        format!("{}{}", day, match day {
-            1 | 21 | 31 => "st",
-            2 | 22 => "nd",
-            3 | 23 => "rd",
-            _ => "th"
-        })
-    }
-    
-    fn get_month(time: &chrono::prelude::DateTime<chrono::Local>) -> String {
-        String::from(match time.month() {
-            1  => {"January"},
-            2  => {"February"},
-            3  => {"March"},
-            4  => {"April"},
-            5  => {"May"},
-            6  => {"June"},
-            7  => {"July"},
-            8  => {"August"},
-            9  => {"September"},
-            10 => {"October"},
-            11 => {"November"},
-            _  => {"December"},
-        })
-    }
-    
-    fn get_year(time: &chrono::prelude::DateTime<chrono::Local>) -> String {
-        format!("{}", time.year())
-    }
 }
 
@@ -7,7 +7,7 @@ use vte::Perform;
 const SCROLL_SENSITIVITY: f64 = 0.05;
 const SCROLL_LOG_TIME: f64 = 0.75;
 
-#[derive(PartialEq, Eq, Debug, Default)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
 pub enum KeyModifiers {
     Shift,
     #[default] Command,
@@ -15,9 +15,13 @@ pub enum KeyModifiers {
     Control,
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum KeyCode {
+    // backspace (the delete key on a mac)
     Delete,
+    // the key that deletes in front of the cursor
+    ForwardDelete,
+    Insert,
     Tab,
     Left,
     Right,
@@ -25,6 +29,43 @@ pub enum KeyCode {
     Down,
     Return,
     Escape,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    // F1 through F12
+    F (u8),
+}
+
+// a key read from an escape sequence, either a named key or a character
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Key {
+    Code (KeyCode),
+    Char (char),
+}
+
+// only the kitty protocol reports repeats and releases, so everything else comes as a press
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum KeyEventKind {
+    #[default] Press,
+    Repeat,
+    Release,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct KeyEvent {
+    pub key: Key,
+    pub modifiers: Vec <KeyModifiers>,
+    pub kind: KeyEventKind,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum KeyboardProtocol {
+    // the terminal hasn't answered the query yet
+    #[default] Unknown,
+    Legacy,
+    // the kitty keyboard protocol, which has been turned on
+    Kitty,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Default)]
@@ -64,6 +105,14 @@ pub struct KeyParser {
     pub lastPress: u128,
     pub scrollEvents: Vec <(std::time::SystemTime, i8)>,  // the sign is the direction
     pub scrollAccumulate: f64,
+    // the final byte of an escape whose key comes as the next character
+    // (O for SS3 like ^[OP, or [ for the linux console's F1-F5 like ^[[[A)
+    pub escapePrefix: Option <char>,
+    // every key read from an escape sequence this frame, releases included
+    pub keyLog: Vec <KeyEvent>,
+    pub keyboardProtocol: KeyboardProtocol,
+    // anything to send back to the terminal in answer to what it sent (written out after each read)
+    pub response: Vec <u8>,
 }
 
 impl KeyParser {
@@ -71,6 +120,8 @@ impl KeyParser {
         KeyParser {
             keyEvents: std::collections::HashMap::from([
                 (KeyCode::Delete, false),
+                (KeyCode::ForwardDelete, false),
+                (KeyCode::Insert, false),
                 (KeyCode::Tab, false),
                 (KeyCode::Left, false),
                 (KeyCode::Right, false),
@@ -78,6 +129,10 @@ impl KeyParser {
                 (KeyCode::Down, false),
                 (KeyCode::Return, false),
                 (KeyCode::Escape, false),
+                (KeyCode::PageUp, false),
+                (KeyCode::PageDown, false),
+                (KeyCode::Home, false),
+                (KeyCode::End, false),
             ]),
             keyModifiers: vec!(),
             charEvents: vec!(),
@@ -88,6 +143,10 @@ impl KeyParser {
             lastPress: 0,
             scrollEvents: vec![],
             scrollAccumulate: 0.0,
+            escapePrefix: None,
+            keyLog: vec![],
+            keyboardProtocol: KeyboardProtocol::Unknown,
+            response: vec![],
         }
     }
 
@@ -122,7 +181,10 @@ impl KeyParser {
         self.keyModifiers.clear();
         self.mouseModifiers.clear();
         self.keyEvents.clear();
+        self.keyLog.clear();
+        self.response.clear();
         self.inEscapeSeq = false;
+        self.escapePrefix = None;
         self.UpdateScroll();
 
         if let Some(event) = &mut self.mouseEvent {
@@ -152,10 +214,6 @@ impl KeyParser {
         self.keyModifiers.contains(modifier)
     }
 
-    pub fn ContainsMouseModifier (&self, modifier: KeyModifiers) -> bool {
-        self.mouseModifiers.contains(&modifier)
-    }
-
     pub fn ContainsKeyCode (&self, key: KeyCode) -> bool {
         *self.keyEvents.get(&key).unwrap_or(&false)
     }
@@ -208,15 +266,14 @@ impl KeyParser {
         (x, y): (u16, u16),
         c: char
     ) {
-        if let Some(event) = &mut self.mouseEvent {
-            if matches!(eventType, MouseEventType::Left) &&
-                event.position != (x, y) &&
-                matches!(event.state, MouseState::Hold) &&
-                c == 'M'
-            {
-                event.position = (x, y);
-                return;
-            }
+        if let Some(event) = &mut self.mouseEvent &&
+            matches!(eventType, MouseEventType::Left) &&
+            event.position != (x, y) &&
+            matches!(event.state, MouseState::Hold) &&
+            c == 'M'
+        {
+            event.position = (x, y);
+            return;
         }
 
         self.mouseEvent = Some(MouseEvent {
@@ -232,74 +289,11 @@ impl KeyParser {
         });
     }
 
+    // Codes set up by hand in the terminal for combinations it has no sequence for, sent as ^[[3;<code>~
+    // They start at 17 since anything lower is a forward delete with xterm's modifiers (see GetModifiers), so
+    // what the lower ones did is bound to keys terminals send on their own (alt/control + backspace, control + arrows)
     fn HandleCustomEscapeCodes (&mut self, numbers: &[u16]) {
         match numbers[1] {
-            2 => {
-                self.keyEvents.insert(KeyCode::Delete, true);
-                self.keyModifiers.push(KeyModifiers::Shift);
-            }
-            3 => {
-                self.keyEvents.insert(KeyCode::Delete, true);
-                self.keyModifiers.push(KeyModifiers::Option);
-            }
-            4 => {
-                self.keyEvents.insert(KeyCode::Left, true);
-                self.keyModifiers.push(KeyModifiers::Command);
-            }
-            5 => {
-                self.keyEvents.insert(KeyCode::Right, true);
-                self.keyModifiers.push(KeyModifiers::Command);
-            }
-            6 => {
-                self.keyEvents.insert(KeyCode::Up, true);
-                self.keyModifiers.push(KeyModifiers::Command);
-            }
-            7 => {
-                self.keyEvents.insert(KeyCode::Down, true);
-                self.keyModifiers.push(KeyModifiers::Command);
-            }
-            8 => {
-                self.keyEvents.insert(KeyCode::Delete, true);
-                self.keyModifiers.push(KeyModifiers::Option);
-                self.keyModifiers.push(KeyModifiers::Shift);
-            }
-            9 => {
-                self.keyEvents.insert(KeyCode::Delete, true);
-                self.keyModifiers.push(KeyModifiers::Command);
-            }
-            10 => {
-                self.keyEvents.insert(KeyCode::Delete, true);
-                self.keyModifiers.push(KeyModifiers::Command);
-                self.keyModifiers.push(KeyModifiers::Shift);
-            }
-            11 => {
-                self.keyModifiers.push(KeyModifiers::Command);
-                self.charEvents.push('s');  // command + s
-            }
-            12 => {  // lrud
-                self.keyEvents.insert(KeyCode::Left, true);
-                self.keyModifiers.push(KeyModifiers::Command);
-                self.keyModifiers.push(KeyModifiers::Shift);
-            }
-            13 => {
-                self.keyEvents.insert(KeyCode::Right, true);
-                self.keyModifiers.push(KeyModifiers::Command);
-                self.keyModifiers.push(KeyModifiers::Shift);
-            }
-            14 => {
-                self.keyEvents.insert(KeyCode::Up, true);
-                self.keyModifiers.push(KeyModifiers::Command);
-                self.keyModifiers.push(KeyModifiers::Shift);
-            }
-            15 => {
-                self.keyEvents.insert(KeyCode::Down, true);
-                self.keyModifiers.push(KeyModifiers::Command);
-                self.keyModifiers.push(KeyModifiers::Shift);
-            }
-            16 => {
-                self.keyModifiers.push(KeyModifiers::Command);
-                self.charEvents.push('c');
-            }
             17 => {
                 self.keyModifiers.push(KeyModifiers::Command);
                 self.charEvents.push('v');
@@ -329,79 +323,110 @@ impl KeyParser {
         }
     }
 
-    fn HandleControlArrows (&mut self, _numbers: &[u16], c: char) {
-        match c {
-            'D' => {
-                self.keyModifiers.push(KeyModifiers::Control);
-                self.keyEvents.insert(KeyCode::Left, true);
-            },
-            'C' => {
-                self.keyModifiers.push(KeyModifiers::Control);
-                self.keyEvents.insert(KeyCode::Right, true);
-            },
-            'A' => {
-                self.keyModifiers.push(KeyModifiers::Control);
-                self.keyEvents.insert(KeyCode::Up, true);
-            },
-            'B' => {
-                self.keyModifiers.push(KeyModifiers::Control);
-                self.keyEvents.insert(KeyCode::Down, true);
-            },
-            _ => {}  // control + arrows
+    // xterm sends the modifiers as 1 plus a bit each for shift (1), alt (2), control (4) and meta (8)
+    //   e.g. ^[[1;6C is control + shift + right
+    // alt is Option and meta is Command, going by a mac's keyboard
+    // the kitty protocol uses the same bits (calling meta super), and adds some above them that are left out
+    fn GetModifiers (parameter: u16) -> Vec <KeyModifiers> {
+        let bits = parameter.saturating_sub(1);
+        let mut modifiers = vec![];
+        if bits & 1 != 0 {  modifiers.push(KeyModifiers::Shift);  }
+        if bits & 2 != 0 {  modifiers.push(KeyModifiers::Option);  }
+        if bits & 4 != 0 {  modifiers.push(KeyModifiers::Control);  }
+        if bits & 8 != 0 {  modifiers.push(KeyModifiers::Command);  }
+        modifiers
+    }
+
+    // the kitty protocol puts the event type after the modifiers (^[[1;5:3D is control + left being let go)
+    fn GetEventKind (params: &vte::Params) -> KeyEventKind {
+        match params.iter().nth(1).and_then(|param| param.get(1)) {
+            Some(2) => KeyEventKind::Repeat,
+            Some(3) => KeyEventKind::Release,
+            _ => KeyEventKind::Press,
         }
     }
 
-    fn HandleStandardEscapeCodes (&mut self, numbers: &Vec <u16>, c: char) {
-        match c as u8 {
-            0x5A => {
-                self.keyEvents.insert(KeyCode::Tab, true);
-                self.keyModifiers.push(KeyModifiers::Shift);
-            },
-            0x44 => {
-                self.keyEvents.insert(KeyCode::Left, true);
-                if *numbers == [1, 3] {
-                    self.keyModifiers.push(KeyModifiers::Option);
-                } else if *numbers == [1, 2] {
-                    self.keyModifiers.push(KeyModifiers::Shift);
-                } else if *numbers == [1, 4] {
-                    self.keyModifiers.push(KeyModifiers::Option);
-                    self.keyModifiers.push(KeyModifiers::Shift);
-                }
-            },
-            0x43 => {
-                self.keyEvents.insert(KeyCode::Right, true);
-                if *numbers == [1, 3] {
-                    self.keyModifiers.push(KeyModifiers::Option);
-                } else if *numbers == [1, 2] {
-                    self.keyModifiers.push(KeyModifiers::Shift);
-                } else if *numbers == [1, 4] {
-                    self.keyModifiers.push(KeyModifiers::Option);
-                    self.keyModifiers.push(KeyModifiers::Shift);
-                }
-            },
-            0x41 => {
-                self.keyEvents.insert(KeyCode::Up, true);
-                if *numbers == [1, 3] {
-                    self.keyModifiers.push(KeyModifiers::Option);
-                } else if *numbers == [1, 2] {
-                    self.keyModifiers.push(KeyModifiers::Shift);
-                } else if *numbers == [1, 4] {
-                    self.keyModifiers.push(KeyModifiers::Option);
-                    self.keyModifiers.push(KeyModifiers::Shift);
-                }
-            },
-            0x42 => {
-                self.keyEvents.insert(KeyCode::Down, true);
-                if *numbers == [1, 3] {
-                    self.keyModifiers.push(KeyModifiers::Option);
-                } else if *numbers == [1, 2] {
-                    self.keyModifiers.push(KeyModifiers::Shift);
-                } else if *numbers == [1, 4] {
-                    self.keyModifiers.push(KeyModifiers::Option);
-                    self.keyModifiers.push(KeyModifiers::Shift);
-                }
-            },
-            _ => {},
+    // Records a key read from an escape sequence
+    // Releases only go in the log, so anything looking at keyEvents or charEvents sees a key once per press (and each repeat)
+    fn PushKey (&mut self, key: Key, modifiers: u16, kind: KeyEventKind) {
+        let modifiers = KeyParser::GetModifiers(modifiers);
+        if kind != KeyEventKind::Release {
+            match key {
+                Key::Code(code) => {  self.keyEvents.insert(code, true);  },
+                Key::Char(chr) => self.charEvents.push(chr),
+            }
+            self.keyModifiers.extend_from_slice(&modifiers);
+        }
+        self.keyLog.push(KeyEvent { key, modifiers, kind });
+    }
+
+    // the kitty protocol's ^[[<key>;<modifiers>u, where the key is the unicode codepoint (lower case) for anything
+    // that types, a control character for tab/return/escape/backspace, or one from the private use area
+    fn GetKittyKey (number: u16) -> Option <Key> {
+        Some(Key::Code(match number {
+            9 => KeyCode::Tab,
+            13 | 57414 => KeyCode::Return,  // 57414 is the keypad's enter
+            27 => KeyCode::Escape,
+            127 => KeyCode::Delete,
+            // F13-F35
+            57376..=57398 => KeyCode::F((number - 57376 + 13) as u8),
+            // the keypad's keys when num lock is off
+            57417 => KeyCode::Left,
+            57418 => KeyCode::Right,
+            57419 => KeyCode::Up,
+            57420 => KeyCode::Down,
+            57421 => KeyCode::PageUp,
+            57422 => KeyCode::PageDown,
+            57423 => KeyCode::Home,
+            57424 => KeyCode::End,
+            57425 => KeyCode::Insert,
+            57426 => KeyCode::ForwardDelete,
+            // the rest of the private use area (the keypad's digits, media keys, the modifiers themselves...)
+            57344..=63743 => return None,
+            _ => return char::from_u32(number as u32).filter(|chr| !chr.is_control()).map(Key::Char),
+        }))
+    }
+
+    // the keys sent as ^[[<number>~ (or ^[[<number>;<modifiers>~)
+    // 11-14 are F1-F4 on rxvt and older xterms, and the gaps (16, 22) are how the VT220 laid them out
+    fn GetTildeKey (number: u16) -> Option <KeyCode> {
+        match number {
+            1 | 7 => Some(KeyCode::Home),
+            2 => Some(KeyCode::Insert),
+            3 => Some(KeyCode::ForwardDelete),
+            4 | 8 => Some(KeyCode::End),
+            5 => Some(KeyCode::PageUp),
+            6 => Some(KeyCode::PageDown),
+            11..=15 => Some(KeyCode::F((number - 10) as u8)),
+            17..=21 => Some(KeyCode::F((number - 11) as u8)),
+            23 | 24 => Some(KeyCode::F((number - 12) as u8)),
+            _ => None,
+        }
+    }
+
+    // the keys sent as a letter, after either ^[[ (^[[1;<modifiers> with modifiers) or ^[O
+    fn GetLetterKey (letter: char) -> Option <KeyCode> {
+        match letter {
+            'A' => Some(KeyCode::Up),
+            'B' => Some(KeyCode::Down),
+            'C' => Some(KeyCode::Right),
+            'D' => Some(KeyCode::Left),
+            'H' => Some(KeyCode::Home),
+            'F' => Some(KeyCode::End),
+            'P'..='S' => Some(KeyCode::F(letter as u8 - b'P' + 1)),
+            _ => None,
+        }
+    }
+
+    // the key after an SS3 (^[O) or the linux console's ^[[[
+    fn HandleEscapePrefix (&mut self, prefix: char, chr: char) {
+        let key = match (prefix, chr) {
+            ('[', 'A'..='E') => Some(KeyCode::F(chr as u8 - b'A' + 1)),
+            ('[', _) => None,
+            _ => KeyParser::GetLetterKey(chr),
+        };
+        if let Some(key) = key {
+            self.keyEvents.insert(key, true);
         }
     }
 
@@ -420,7 +445,53 @@ pub async fn disableMouseCapture() {
     let _ = stdout.write_all(b"\x1B[?1003l"); // Disable motion events
 }
 
+// Asks whether the terminal has the kitty keyboard protocol
+// A terminal with it answers ^[[?<flags>u before the device attributes (^[[c), which every terminal answers,
+// so getting the attributes without the flags first means it only speaks the legacy encoding
+pub fn queryKeyboardEnhancement(out: &mut impl Write) {
+    let _ = out.write_all(b"\x1B[?u\x1B[c");
+    let _ = out.flush();
+}
+
+// Turns on the kitty protocol's disambiguated escape codes (1) and event types (2)
+// Control + i, control + shift + a key... then all get their own codes, and releases get reported
+pub fn enableKeyboardEnhancement(out: &mut impl Write) {
+    let _ = out.write_all(b"\x1B[>3u");
+    let _ = out.flush();
+}
+
+pub fn disableKeyboardEnhancement(out: &mut impl Write) {
+    let _ = out.write_all(b"\x1B[<u");
+    let _ = out.flush();
+}
+
 impl KeyParser {
+    // Parses whatever was read from stdin in one go
+    // An escape byte on its own is the escape key rather than the start of a sequence
+    pub fn ParseBytes (&mut self, parser: &mut vte::Parser, bytes: &[u8]) {
+        self.bytes = bytes.len();
+        if bytes == [0x1B] {
+            self.keyEvents.insert(KeyCode::Escape, true);
+            return;
+        }
+        // alt + backspace is ^[ followed by the backspace (^? or ^H), which vte would take as the start of
+        // a sequence and swallow the next key into, so it's picked out before the rest is parsed
+        let mut rest = bytes;
+        while let Some(index) = rest.windows(2).position(|pair| pair[0] == 0x1B && matches!(pair[1], 0x7F | 0x08)) {
+            parser.advance(self, &rest[..index]);
+            self.SetPressTime();
+            self.keyEvents.insert(KeyCode::Delete, true);
+            self.keyModifiers.push(KeyModifiers::Option);
+            rest = &rest[index + 2..];
+        }
+        parser.advance(self, rest);
+    }
+
+    // whether the events will keep changing without new input (a scroll easing off over the next few frames)
+    pub fn IsSettling (&self) -> bool {
+        self.mouseEvent.as_ref().is_some_and(|event| matches!(event.eventType, MouseEventType::Down | MouseEventType::Up))
+    }
+
     pub fn SetPressTime (&mut self) {
         self.lastPress = std::time::SystemTime::now()
             .duration_since(std::time::SystemTime::UNIX_EPOCH)
@@ -431,14 +502,18 @@ impl KeyParser {
 
 impl Perform for KeyParser {
     fn print(&mut self, chr: char) {
+        if let Some(prefix) = self.escapePrefix.take() {
+            self.SetPressTime();
+            self.HandleEscapePrefix(prefix, chr);
+            return;
+        }
         //println!("char {}: '{}'", chr as u8, chr);
-        if self.inEscapeSeq || self.bytes > 1 {
-            match chr as u8 {
-                17 => {
-                    self.charEvents.push('w');
-                    self.keyModifiers.push(KeyModifiers::Option);
-                },
-                _ => {}
+        // several ascii bytes at once are the remains of an escape sequence, but a single
+        // non-ascii character (é, 漢, emoji...) takes several bytes on its own
+        if self.inEscapeSeq || (self.bytes > 1 && chr.is_ascii()) {
+            if chr as u8 == 17 {
+                self.charEvents.push('w');
+                self.keyModifiers.push(KeyModifiers::Option);
             }
 
             return;
@@ -449,7 +524,7 @@ impl Perform for KeyParser {
             self.keyEvents.insert(KeyCode::Delete, true);
             return;
         }
-        if !(chr.is_ascii_graphic() || chr.is_whitespace()) {  return;  }
+        if chr.is_control() {  return;  }
         //println!("char {}: '{}'", chr as u8, chr);
         self.charEvents.push(chr);
     }
@@ -459,11 +534,12 @@ impl Perform for KeyParser {
         self.SetPressTime();
 
         // control + ...
-        // 3 = c; 22 = v; 26 = z; 6 = f; 1 = a; 24 = x; 19 = s; 21 = u; r = 18
+        // 3 = c; 22 = v; 26 = z; 6 = f; 1 = a; 24 = x; 19 = s; 21 = u; r = 18; t = 20
         // left ^[[1;5D right ^[[1;5C up ^[[1;5A down ^[[1;5B
         // control u and control r and necessary for undo and redo bc/
         // control + key and control + shift + key don't send unique
-        // escape codes for some odd reason
+        // escape codes for some odd reason (unless the terminal has the
+        // kitty protocol, in which case they come through csi_dispatch)
 
         match byte {
             0x1B => {
@@ -511,6 +587,10 @@ impl Perform for KeyParser {
                 self.keyModifiers.push(KeyModifiers::Control);
                 self.charEvents.push('r');
             },
+            20 => {
+                self.keyModifiers.push(KeyModifiers::Control);
+                self.charEvents.push('t');
+            },
             0x08 => {
                 self.keyModifiers.push(KeyModifiers::Control);
                 self.keyEvents.insert(KeyCode::Delete, true);
@@ -525,32 +605,243 @@ impl Perform for KeyParser {
     }
 
     #[inline(always)]
-    fn csi_dispatch(&mut self, params: &vte::Params, _: &[u8], _: bool, c: char) {
+    fn csi_dispatch(&mut self, params: &vte::Params, intermediates: &[u8], _: bool, c: char) {
         self.inEscapeSeq = false;  // resetting the escape sequence
         self.SetPressTime();
 
         let numbers: Vec <u16> = params.iter().map(|p| p[0]).collect();
 
-        // mouse handling
+        // mouse handling (^[[<0;10;5M)
         if c == 'M' || c == 'm' {
             self.HandleMouseEscapeCodes(&numbers, c);
             return;
         }
 
+        // anything else with a private marker (^[[?...) is a reply to a query rather than a key
+        if !intermediates.is_empty() {
+            match (intermediates, c) {
+                // the kitty protocol's flags, so it's there to turn on
+                (b"?", 'u') => {
+                    self.keyboardProtocol = KeyboardProtocol::Kitty;
+                    enableKeyboardEnhancement(&mut self.response);
+                },
+                // the device attributes, which come last
+                (b"?", 'c') if self.keyboardProtocol == KeyboardProtocol::Unknown => {
+                    self.keyboardProtocol = KeyboardProtocol::Legacy;
+                },
+                _ => {},
+            }
+            return;
+        }
+        let modifiers = numbers.get(1).copied().unwrap_or(1);
+        let kind = KeyParser::GetEventKind(params);
+
         //for number in &numbers {println!("{}", number);}
-        if c == '~' && numbers.len() == 2 && numbers[0] == 3 {  // this section is for custom escape codes
-            self.HandleCustomEscapeCodes(&numbers);
-        } else if numbers.len() == 2 && numbers[0] == 1 && numbers[1] == 5 {
-            // control + ...
-            // 3 = c; 22 = v; 26 = z; 6 = f; 1 = a; 24 = x; 19 = s; 21 = u; r = 18
-            // left ^[[1;5D right ^[[1;5C up ^[[1;5A down ^[[1;5B
-            // control u and control r and necessary for undo and redo bc/
-            // control + key and control + shift + key don't send unique
-            // escape codes for some odd reason
-            self.HandleControlArrows(&numbers, c);
-        } else {  // this checks existing escape codes of 1 parameter/ending code (they don't end with ~)
-            self.HandleStandardEscapeCodes(&numbers, c);
+        match c {
+            '~' if numbers.len() == 2 && numbers[0] == 3 && numbers[1] > 16 => {  // this section is for custom escape codes
+                self.HandleCustomEscapeCodes(&numbers);
+            },
+            // page up ^[[5~, forward delete ^[[3~, F5 ^[[15~, control + F5 ^[[15;5~...
+            '~' => {
+                if let Some(key) = KeyParser::GetTildeKey(numbers[0]) {
+                    self.PushKey(Key::Code(key), modifiers, kind);
+                }
+            },
+            // the kitty protocol: control + i ^[[105;5u, escape ^[[27u, control + shift + z ^[[122;6u...
+            'u' => {
+                if let Some(key) = KeyParser::GetKittyKey(numbers[0]) {
+                    self.PushKey(key, modifiers, kind);
+                }
+            },
+            // the linux console sends F1-F5 as ^[[[A through ^[[[E, with the letter coming after
+            '[' => {
+                self.escapePrefix = Some('[');
+            },
+            // shift + tab
+            'Z' => {
+                self.keyEvents.insert(KeyCode::Tab, true);
+                self.keyModifiers.push(KeyModifiers::Shift);
+            },
+            // arrows ^[[D, home ^[[H, F1 ^[[P... and with modifiers, control + left ^[[1;5D
+            _ => {
+                if let Some(key) = KeyParser::GetLetterKey(c) {
+                    self.PushKey(Key::Code(key), modifiers, kind);
+                }
+            },
+        }
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], _: bool, byte: u8) {
+        self.SetPressTime();
+        if !intermediates.is_empty() {  return;  }
+        match byte {
+            // SS3, which the arrows, home/end and F1-F4 are sent with in some modes and terminals (^[OP)
+            b'O' => {
+                self.escapePrefix = Some('O');
+            },
+            // alt + a key sends the key after an escape
+            0x21..=0x7E => {
+                self.keyModifiers.push(KeyModifiers::Option);
+                self.charEvents.push(byte as char);
+            },
+            _ => {},
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a new parser given the bytes as a single read from stdin
+    fn Parse (bytes: &[u8]) -> KeyParser {
+        let mut keyParser = KeyParser::new();
+        keyParser.ParseBytes(&mut vte::Parser::new(), bytes);
+        keyParser
+    }
+
+    #[test]
+    fn DecodesTildeKeys () {
+        let expected = [
+            (1, Some(KeyCode::Home)), (7, Some(KeyCode::Home)),
+            (2, Some(KeyCode::Insert)), (3, Some(KeyCode::ForwardDelete)),
+            (4, Some(KeyCode::End)), (8, Some(KeyCode::End)),
+            (5, Some(KeyCode::PageUp)), (6, Some(KeyCode::PageDown)),
+            (11, Some(KeyCode::F(1))), (15, Some(KeyCode::F(5))),
+            (17, Some(KeyCode::F(6))), (21, Some(KeyCode::F(10))),
+            (23, Some(KeyCode::F(11))), (24, Some(KeyCode::F(12))),
+            // the gaps in the VT220's layout
+            (16, None), (22, None), (25, None),
+        ];
+        for (number, key) in expected {
+            assert_eq!(KeyParser::GetTildeKey(number), key, "^[[{}~", number);
+        }
+    }
+
+    #[test]
+    fn DecodesLetterKeys () {
+        let expected = [
+            ('A', Some(KeyCode::Up)), ('B', Some(KeyCode::Down)),
+            ('C', Some(KeyCode::Right)), ('D', Some(KeyCode::Left)),
+            ('H', Some(KeyCode::Home)), ('F', Some(KeyCode::End)),
+            ('P', Some(KeyCode::F(1))), ('S', Some(KeyCode::F(4))),
+            ('T', None), ('a', None),
+        ];
+        for (letter, key) in expected {
+            assert_eq!(KeyParser::GetLetterKey(letter), key, "^[[{}", letter);
+        }
+    }
+
+    #[test]
+    fn DecodesModifiers () {
+        assert_eq!(KeyParser::GetModifiers(0), vec![]);
+        assert_eq!(KeyParser::GetModifiers(1), vec![]);
+        assert_eq!(KeyParser::GetModifiers(2), vec![KeyModifiers::Shift]);
+        assert_eq!(KeyParser::GetModifiers(6), vec![KeyModifiers::Shift, KeyModifiers::Control]);
+        assert_eq!(KeyParser::GetModifiers(16), vec![
+            KeyModifiers::Shift, KeyModifiers::Option, KeyModifiers::Control, KeyModifiers::Command,
+        ]);
+
+        let keyParser = Parse(b"\x1b[1;6C");
+        assert!(keyParser.ContainsKeyCode(KeyCode::Right));
+        assert_eq!(keyParser.keyModifiers, vec![KeyModifiers::Shift, KeyModifiers::Control]);
+    }
+
+    #[test]
+    fn DecodesSs3Keys () {
+        assert!(Parse(b"\x1bOP").ContainsKeyCode(KeyCode::F(1)));
+        assert!(Parse(b"\x1bOA").ContainsKeyCode(KeyCode::Up));
+
+        // alt + shift + o sends ^[O too, which mustn't eat the key typed in the next frame
+        let mut parser = vte::Parser::new();
+        let mut keyParser = KeyParser::new();
+        keyParser.ParseBytes(&mut parser, b"\x1bO");
+        keyParser.ClearEvents();
+        keyParser.ParseBytes(&mut parser, b"P");
+        assert_eq!(keyParser.charEvents, vec!['P']);
+        assert!(!keyParser.ContainsKeyCode(KeyCode::F(1)));
+    }
+
+    #[test]
+    fn DecodesAltBackspace () {
+        for bytes in [b"\x1b\x7f", b"\x1b\x08"] {
+            let mut parser = vte::Parser::new();
+            let mut keyParser = KeyParser::new();
+            keyParser.ParseBytes(&mut parser, bytes);
+            assert!(keyParser.ContainsKeyCode(KeyCode::Delete), "{:?}", bytes);
+            assert_eq!(keyParser.keyModifiers, vec![KeyModifiers::Option]);
+            assert!(!keyParser.ContainsKeyCode(KeyCode::ForwardDelete));
+
+            // the next key is typed as normal, rather than being taken as alt + the key
+            keyParser.ClearEvents();
+            keyParser.ParseBytes(&mut parser, b"a");
+            assert_eq!(keyParser.charEvents, vec!['a']);
+            assert!(keyParser.keyModifiers.is_empty());
+        }
+    }
+
+    #[test]
+    fn DecodesKittyKeys () {
+        // control + i, which is tab in the legacy encoding
+        let keyParser = Parse(b"\x1b[105;5u");
+        assert_eq!(keyParser.charEvents, vec!['i']);
+        assert_eq!(keyParser.keyModifiers, vec![KeyModifiers::Control]);
+        assert!(!keyParser.ContainsKeyCode(KeyCode::Tab));
+        assert!(Parse(b"\x1b[9u").ContainsKeyCode(KeyCode::Tab));
+
+        let keyParser = Parse(b"\x1b[27u");
+        assert!(keyParser.ContainsKeyCode(KeyCode::Escape));
+        assert!(keyParser.keyModifiers.is_empty());
+
+        let keyParser = Parse(b"\x1b[122;6u");
+        assert_eq!(keyParser.charEvents, vec!['z']);
+        assert_eq!(keyParser.keyModifiers, vec![KeyModifiers::Shift, KeyModifiers::Control]);
+    }
+
+    #[test]
+    fn LogsKittyReleases () {
+        let keyParser = Parse(b"\x1b[97;1:3u");
+        assert!(keyParser.charEvents.is_empty());
+        assert_eq!(keyParser.keyLog, vec![KeyEvent { key: Key::Char('a'), modifiers: vec![], kind: KeyEventKind::Release }]);
+
+        let keyParser = Parse(b"\x1b[27;5:3u");
+        assert!(!keyParser.ContainsKeyCode(KeyCode::Escape));
+        assert!(keyParser.keyModifiers.is_empty());
+        assert_eq!(keyParser.keyLog, vec![KeyEvent {
+            key: Key::Code(KeyCode::Escape), modifiers: vec![KeyModifiers::Control], kind: KeyEventKind::Release,
+        }]);
+
+        // a held key repeats like another press
+        let keyParser = Parse(b"\x1b[1;1:2D");
+        assert!(keyParser.ContainsKeyCode(KeyCode::Left));
+        assert_eq!(keyParser.keyLog[0].kind, KeyEventKind::Repeat);
+    }
+
+    #[test]
+    fn TurnsOnTheKittyProtocolOnceAnswered () {
+        let mut parser = vte::Parser::new();
+        let mut keyParser = KeyParser::new();
+        keyParser.ParseBytes(&mut parser, b"a");
+        assert_eq!(keyParser.keyboardProtocol, KeyboardProtocol::Unknown);
+        assert!(keyParser.response.is_empty());
+
+        // the flags come back first, then the device attributes
+        keyParser.ClearEvents();
+        keyParser.ParseBytes(&mut parser, b"\x1b[?0u");
+        assert_eq!(keyParser.keyboardProtocol, KeyboardProtocol::Kitty);
+        assert_eq!(keyParser.response, b"\x1b[>3u");
+
+        keyParser.ClearEvents();
+        keyParser.ParseBytes(&mut parser, b"\x1b[?62;22c");
+        assert_eq!(keyParser.keyboardProtocol, KeyboardProtocol::Kitty);
+        assert!(keyParser.response.is_empty());
+    }
+
+    #[test]
+    fn FallsBackWithoutTheKittyProtocol () {
+        let keyParser = Parse(b"\x1b[?62;22c");
+        assert_eq!(keyParser.keyboardProtocol, KeyboardProtocol::Legacy);
+        assert!(keyParser.response.is_empty());
+        assert!(keyParser.charEvents.is_empty() && keyParser.keyLog.is_empty());
+    }
+}
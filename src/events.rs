@@ -0,0 +1,177 @@
+// everything the main loop waits on (input, resizes and timers) merged into one stream,
+// so a frame is only drawn when something actually happened
+use std::io::Read;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    // whatever was read from stdin in one go
+    Input(Vec<u8>),
+    // the terminal's new size (columns, rows)
+    Resize((u16, u16)),
+    // a timer asked for another frame
+    Tick,
+    // stdin was closed, so nothing more is coming
+    Closed,
+}
+
+pub struct Events {
+    receiver: mpsc::UnboundedReceiver<Event>,
+    // the soonest a tick was asked for
+    tick: Option<tokio::time::Instant>,
+    tasks: Vec<tokio::task::JoinHandle<()>>,
+}
+
+impl Drop for Events {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+impl Events {
+    // starts listening to stdin and for resizes (has to be called inside the runtime)
+    pub fn new() -> Self {
+        Events::from_sources(std::io::stdin(), watch_size)
+    }
+
+    // the events read from the input, along with any the watcher sends (tests script both)
+    fn from_sources<F>(input: impl Read + Send + 'static, watch: impl FnOnce(mpsc::UnboundedSender<Event>) -> F) -> Self
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        // reading stdin blocks, and the runtime would wait on it when shutting down if it were one of its tasks
+        let input_sender = sender.clone();
+        std::thread::spawn(move || read_input(input, input_sender));
+        let tasks = vec![tokio::spawn(watch(sender))];
+        Events {
+            receiver,
+            tick: None,
+            tasks,
+        }
+    }
+
+    // asks for a tick after the delay (if one's already coming sooner, that one does)
+    pub fn tick_after(&mut self, delay: std::time::Duration) {
+        let at = tokio::time::Instant::now() + delay;
+        self.tick = Some(self.tick.map_or(at, |tick| tick.min(at)));
+    }
+
+    // waits on the next event; None once every source is gone
+    pub async fn next(&mut self) -> Option<Event> {
+        let Some(tick) = self.tick else {
+            return self.receiver.recv().await;
+        };
+        tokio::select! {
+            event = self.receiver.recv() => event,
+            _ = tokio::time::sleep_until(tick) => {
+                self.tick = None;
+                Some(Event::Tick)
+            },
+        }
+    }
+}
+
+fn read_input(mut input: impl Read, sender: mpsc::UnboundedSender<Event>) {
+    let mut buffer = [0; 128];
+    loop {
+        let event = match input.read(&mut buffer) {
+            Ok(0) | Err(_) => Event::Closed,
+            Ok(n) => Event::Input(buffer[..n].to_vec()),
+        };
+        let closed = event == Event::Closed;
+        if sender.send(event).is_err() || closed {  return;  }
+    }
+}
+
+// the terminal sends SIGWINCH whenever it's resized
+#[cfg(unix)]
+async fn watch_size(sender: mpsc::UnboundedSender<Event>) {
+    use tokio::signal::unix::{signal, SignalKind};
+    let Ok(mut resizes) = signal(SignalKind::window_change()) else {  return;  };
+    while resizes.recv().await.is_some() {
+        let Ok(size) = crossterm::terminal::size() else {  continue;  };
+        if sender.send(Event::Resize(size)).is_err() {  return;  }
+    }
+}
+
+// without signals the size is checked every so often instead
+#[cfg(not(unix))]
+async fn watch_size(sender: mpsc::UnboundedSender<Event>) {
+    let mut last = crossterm::terminal::size().ok();
+    loop {
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+        let size = crossterm::terminal::size().ok();
+        if size == last {  continue;  }
+        last = size;
+        if let Some(size) = size && sender.send(Event::Resize(size)).is_err() {  return;  }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    // input that's handed over a read at a time, and ends once the sender's dropped
+    struct ScriptedInput(std::sync::mpsc::Receiver<Vec<u8>>);
+
+    impl Read for ScriptedInput {
+        fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+            let Ok(bytes) = self.0.recv() else {  return Ok(0);  };
+            buffer[..bytes.len()].copy_from_slice(&bytes);
+            Ok(bytes.len())
+        }
+    }
+
+    // events fed from the returned input and resize senders
+    fn scripted() -> (Events, std::sync::mpsc::Sender<Vec<u8>>, mpsc::UnboundedSender<(u16, u16)>) {
+        let (input, reads) = std::sync::mpsc::channel();
+        let (resize, mut sizes) = mpsc::unbounded_channel();
+        let events = Events::from_sources(ScriptedInput(reads), |sender| async move {
+            while let Some(size) = sizes.recv().await {
+                if sender.send(Event::Resize(size)).is_err() {  return;  }
+            }
+        });
+        (events, input, resize)
+    }
+
+    async fn next(events: &mut Events) -> Option<Event> {
+        tokio::time::timeout(Duration::from_secs(5), events.next()).await.expect("no event came")
+    }
+
+    #[tokio::test]
+    async fn input_and_resizes_come_out_in_order() {
+        let (mut events, input, resize) = scripted();
+        input.send(b"a".to_vec()).unwrap();
+        assert_eq!(next(&mut events).await, Some(Event::Input(b"a".to_vec())));
+        resize.send((80, 20)).unwrap();
+        assert_eq!(next(&mut events).await, Some(Event::Resize((80, 20))));
+        // an escape sequence read in one go stays together
+        input.send(b"\x1b[A".to_vec()).unwrap();
+        assert_eq!(next(&mut events).await, Some(Event::Input(b"\x1b[A".to_vec())));
+        resize.send((100, 24)).unwrap();
+        assert_eq!(next(&mut events).await, Some(Event::Resize((100, 24))));
+
+        drop(input);
+        assert_eq!(next(&mut events).await, Some(Event::Closed));
+    }
+
+    #[tokio::test]
+    async fn ticks_are_merged_into_the_soonest() {
+        let (mut events, input, _resize) = scripted();
+        events.tick_after(Duration::from_millis(20));
+        events.tick_after(Duration::from_secs(60));
+        events.tick_after(Duration::from_millis(10));
+        assert_eq!(next(&mut events).await, Some(Event::Tick));
+        // only the one tick
+        assert!(tokio::time::timeout(Duration::from_millis(50), events.next()).await.is_err());
+
+        // input that's already come in doesn't wait on a tick
+        events.tick_after(Duration::from_secs(60));
+        input.send(b"b".to_vec()).unwrap();
+        assert_eq!(next(&mut events).await, Some(Event::Input(b"b".to_vec())));
+    }
+}
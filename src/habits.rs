@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+use crate::config::HabitDefinition;
+
+// the format days are keyed by (sorts chronologically as a string)
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// completions for every day, saved to habits.json alongside logs.json
+// day -> habit name -> times done that day
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+pub struct HabitLog(BTreeMap<String, BTreeMap<String, u32>>);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HabitStats {
+    // fraction (0 through 1) of days completed since the habit was first completed
+    pub completion_rate: f64,
+    // consecutive days up to today (or yesterday, so an unfinished today doesn't break it)
+    pub current_streak: usize,
+    pub best_streak: usize,
+}
+
+impl HabitLog {
+    // like the config, a file that can't be parsed is an error instead of an empty log
+    // (which the next save would write over the whole history with)
+    pub fn load() -> std::io::Result<Self> {
+        match std::fs::File::open("habits.json") {
+            Ok(file) => {
+                let reader = std::io::BufReader::new(file);
+                serde_json::from_reader(reader).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(HabitLog::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let file = std::fs::File::create("habits.json")?;
+        serde_json::to_writer(file, &self)?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: chrono::NaiveDate, habit: &str) -> u32 {
+        self.0.get(&day.format(DATE_FORMAT).to_string())
+            .and_then(|habits| habits.get(habit))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn set(&mut self, day: chrono::NaiveDate, habit: &str, count: u32) {
+        let key = day.format(DATE_FORMAT).to_string();
+        if count == 0 {
+            // not keeping zeros around, otherwise the file fills with empty days
+            if let Some(habits) = self.0.get_mut(&key) {
+                habits.remove(habit);
+                if habits.is_empty() {  self.0.remove(&key);  }
+            }
+            return;
+        }
+        self.0.entry(key).or_default().insert(habit.to_string(), count);
+    }
+
+    // checks/unchecks yes/no habits, or adds one to count habits
+    pub fn check_off(&mut self, day: chrono::NaiveDate, habit: &HabitDefinition) {
+        let count = self.get(day, &habit.name);
+        let count = match habit.kind {
+            crate::config::HabitKind::Check => if count > 0 { 0 } else { 1 },
+            crate::config::HabitKind::Count => count + 1,
+        };
+        self.set(day, &habit.name, count);
+    }
+
+    pub fn undo(&mut self, day: chrono::NaiveDate, habit: &HabitDefinition) {
+        let count = self.get(day, &habit.name);
+        self.set(day, &habit.name, count.saturating_sub(1));
+    }
+
+    pub fn is_complete(&self, day: chrono::NaiveDate, habit: &HabitDefinition) -> bool {
+        habit.is_complete(self.get(day, &habit.name))
+    }
+
+    pub fn get_stats(&self, habit: &HabitDefinition, today: chrono::NaiveDate) -> HabitStats {
+        let first_day = self.0.iter()
+            .find(|(_, habits)| habits.get(&habit.name).is_some_and(|count| habit.is_complete(*count)))
+            .and_then(|(day, _)| chrono::NaiveDate::parse_from_str(day, DATE_FORMAT).ok());
+        let Some(first_day) = first_day else {  return HabitStats::default();  };
+        if first_day > today {  return HabitStats::default();  }
+
+        let mut stats = HabitStats::default();
+        let mut completed = 0;
+        let mut streak = 0;
+        let mut total = 0;
+        for day in first_day.iter_days().take_while(|day| *day <= today) {
+            let complete = self.is_complete(day, habit);
+            // today isn't over yet, so only count it once it's done
+            if !complete && day == today {  continue;  }
+            total += 1;
+            if complete {
+                completed += 1;
+                streak += 1;
+                stats.best_streak = stats.best_streak.max(streak);
+            } else {
+                streak = 0;
+            }
+        }
+        stats.current_streak = streak;
+        stats.completion_rate = completed as f64 / total.max(1) as f64;
+        stats
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use chrono::Datelike;
+use crate::config::Config;
+
+// the catalogs built into the binary; others can be dropped in as locales/<code>.json
+static SHIPPED: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.json")),
+    ("es", include_str!("../locales/es.json")),
+];
+
+static LOCALE: std::sync::OnceLock<Locale> = std::sync::OnceLock::new();
+static ENGLISH: std::sync::OnceLock<Locale> = std::sync::OnceLock::new();
+
+#[derive(serde::Deserialize, Debug, Clone)]
+struct DateNames {
+    // filled in with {weekday}, {day}, {ordinal}, {month} and {year}
+    format: String,
+    // starting on monday
+    weekdays: Vec<String>,
+    weekdays_short: Vec<String>,
+    months: Vec<String>,
+    // day of the month -> suffix, with "other" used for any day not listed
+    ordinals: HashMap<String, String>,
+}
+
+// a message catalog along with how dates are written
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct Locale {
+    date: DateNames,
+    messages: HashMap<String, String>,
+}
+
+impl Locale {
+    fn load(code: &str) -> Option<Self> {
+        if let Some((_, catalog)) = SHIPPED.iter().find(|(shipped, _)| *shipped == code) {
+            return serde_json::from_str(catalog).ok();
+        }
+        let file = std::fs::File::open(format!("locales/{}.json", code)).ok()?;
+        serde_json::from_reader(std::io::BufReader::new(file)).ok()
+    }
+
+    // the message for the key, falling back on english and then the key itself
+    pub fn text<'a>(&'a self, key: &'a str) -> &'a str {
+        if let Some(message) = self.messages.get(key) {  return message;  }
+        match english().messages.get(key) {
+            Some(message) => message,
+            None => key,
+        }
+    }
+
+    // the message with each {name} replaced by its argument
+    pub fn format(&self, key: &str, arguments: &[(&str, &str)]) -> String {
+        let mut message = self.text(key).to_string();
+        for (name, value) in arguments {
+            message = message.replace(&format!("{{{}}}", name), value);
+        }
+        message
+    }
+
+    pub fn format_date(&self, date: &impl Datelike) -> String {
+        let day = date.day().to_string();
+        let ordinal = self.date.ordinals.get(&day)
+            .or(self.date.ordinals.get("other"))
+            .map_or("", |suffix| suffix.as_str());
+        self.date.format
+            .replace("{weekday}", self.get_week_day(date))
+            .replace("{day}", &day)
+            .replace("{ordinal}", ordinal)
+            .replace("{month}", self.get_month(date))
+            .replace("{year}", &date.year().to_string())
+    }
+
+    pub fn get_week_day(&self, date: &impl Datelike) -> &str {
+        let index = date.weekday().num_days_from_monday() as usize;
+        self.date.weekdays.get(index).map_or("", |name| name.as_str())
+    }
+
+    pub fn get_short_week_day(&self, date: &impl Datelike) -> &str {
+        let index = date.weekday().num_days_from_monday() as usize;
+        self.date.weekdays_short.get(index).map_or("", |name| name.as_str())
+    }
+
+    pub fn get_month(&self, date: &impl Datelike) -> &str {
+        self.date.months.get(date.month0() as usize).map_or("", |name| name.as_str())
+    }
+
+    // the month number (1 through 12) for a month name written in this locale
+    pub fn parse_month(&self, name: &str) -> Option<u32> {
+        self.date.months.iter().position(|month| month == name).map(|index| index as u32 + 1)
+    }
+}
+
+// picks the locale from the config, otherwise the environment (LANG and co), otherwise english
+// only the first call does anything
+pub fn init(config: &Config) {
+    let from_environment = ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+        .filter_map(|variable| std::env::var(variable).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX");
+    let requested = config.locale.clone().or(from_environment).unwrap_or_default();
+    // "es_ES.UTF-8" -> "es"
+    let code = requested.split(['_', '.', '-', '@']).next().unwrap_or_default().to_lowercase();
+    let _ = LOCALE.set(Locale::load(&code).unwrap_or_else(|| english().clone()));
+}
+
+pub fn get() -> &'static Locale {
+    LOCALE.get().unwrap_or_else(|| english())
+}
+
+pub fn english() -> &'static Locale {
+    ENGLISH.get_or_init(|| Locale::load("en").expect("the english catalog is built in"))
+}
+
+// shorthand for looking up a message in the current locale
+pub fn tr(key: &str) -> &str {
+    get().text(key)
+}
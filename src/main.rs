@@ -1,54 +1,108 @@
-#[allow(non_snake_case)]
 mod TermRender;
-#[allow(non_snake_case)]
 mod eventHandler;
 
 mod entries;
 use entries::Logs;
+mod config;
+use config::Config;
+mod habits;
+use habits::HabitLog;
+mod analysis;
+mod suggestions;
+mod locale;
+use locale::tr;
+mod themes;
+mod events;
+use events::{Event, Events};
+#[cfg(test)]
+mod tests;
 
 use crossterm::terminal::{enable_raw_mode, disable_raw_mode};
 use eventHandler::*;
-use tokio;
-use tokio::io::{self, AsyncReadExt};
+use std::io::Write;
+use tokio::io;
 use crate::TermRender::{Colorize};
+use crate::TermRender::theme::Theme;
+use crate::TermRender::widgets::{self, Widget};
+use crate::TermRender::layout::{Area, Constraint, Layout};
+
+// how soon another frame is drawn while something is still changing without any input
+const SETTLE_TICK: std::time::Duration = std::time::Duration::from_millis(50);
 
 struct App {
     renderer: TermRender::App,
     area: TermRender::Rect,
     logs: Logs,
-    scrolled: usize,
+    config: Config,
     selected: Option<usize>,
     creator_button: Option<CreatorButton>,
     editing_index: Option<usize>,
+    habit_log: HabitLog,
+    habit_tracker: Option<HabitTracker>,
+    analysis: Option<AnalysisView>,
+    mouse_router: TermRender::MouseRouter<App>,
+    // the name of the current theme
+    theme: String,
+    // whether changes are saved to logs.json and habits.json (tests turn this off)
+    persist: bool,
+    // the first save that failed, which ends the program once the frame's done
+    save_error: Option<std::io::Error>,
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(config: Config, habit_log: HabitLog, theme: String) -> Self {
 
-        let save = match std::fs::File::open("logs.json") {
+        let mut save = match std::fs::File::open("logs.json") {
             Ok(logs) => {
                 let reader = std::io::BufReader::new(logs);
                 serde_json::from_reader(reader).unwrap()
             },
             _ => Logs::new(),
         };
+        save.sort_by_date();  // older versions always added to the end, so backdated entries could be out of order
         //save.push(Log::new(String::from("Title"), String::from("Text")));
-        let app = App {
-            renderer: TermRender::App::new(),
+        let mut app = Self::from_parts(TermRender::App::new(), save, config, habit_log, theme);
+        app.save();
+        app
+    }
+    
+    // an app around what's already been loaded, with the name of the theme themes::init set
+    // (tests give it a headless renderer and don't persist anything)
+    fn from_parts(mut renderer: TermRender::App, logs: Logs, config: Config, habit_log: HabitLog, theme: String) -> Self {
+        locale::init(&config);
+        if let Some(frame_rate) = config.max_frame_rate {
+            renderer.SetMaxFrameRate(frame_rate);
+        }
+        App {
+            renderer,
             area: TermRender::Rect::default(),
-            logs: save,
-            scrolled: 0,
+            logs,
+            config,
             selected: None,
             creator_button: None,
             editing_index: None,
-        };
-        Self::save(&app);
-        app
+            habit_log,
+            habit_tracker: None,
+            analysis: None,
+            mouse_router: Self::new_mouse_router(),
+            theme,
+            persist: true,
+            save_error: None,
+        }
     }
     
-    fn save(&self) {
+    fn save(&mut self) {
+        if !self.persist {  return;  }
         let file = std::fs::File::create("logs.json").unwrap();
         serde_json::to_writer(file, &self.logs).unwrap();
+        self.save_habits();
+    }
+    
+    fn save_habits(&mut self) {
+        if !self.persist {  return;  }
+        if let Err(err) = self.habit_log.save() {
+            self.save_error.get_or_insert(err);
+        }
     }
     
     async fn run(&mut self) {
@@ -58,228 +112,493 @@ impl App {
     }
     
     async fn run_internal(&mut self) -> io::Result<()> {
-        let terminal_size = self.renderer.GetTerminalSize()?;
-        self.area = TermRender::Rect {
-            width: terminal_size.0,
-            height: terminal_size.1
-        };
+        self.resize(self.renderer.GetTerminalSize()?);
         
         let mut stdout = std::io::stdout();
         crossterm::execute!(stdout, crossterm::terminal::Clear(crossterm::terminal::ClearType::All))?;
         
         let mut parser = vte::Parser::new();
         let mut key_parser = KeyParser::new();
-        let mut buffer = [0; 128];
-        let mut stdin = io::stdin();
+        let mut events = Events::new();
+        // the kitty keyboard protocol gets turned on once the terminal says it has it
+        queryKeyboardEnhancement(&mut stdout);
         
-        self.scrolled = self.logs.len().saturating_sub(self.area.height as usize / 2 - 1);
+        self.update(&key_parser).await;
         
-        self.render_logs().await;
-        self.renderer.Render(Some((self.area.width, self.area.height)));
-        
-        loop {
-            let term_size = self.renderer.GetTerminalSize()?;
-            self.area = TermRender::Rect {
-                width: term_size.0,
-                height: term_size.1,
-            };
-            
-            // processing the events
+        // nothing is drawn until something happens
+        while let Some(event) = events.next().await {
             key_parser.ClearEvents();
-            let result = tokio::time::timeout(tokio::time::Duration::from_secs_f64(0.2), stdin.read(&mut buffer)).await;
-            if let Ok(Ok(n)) = result {
-                key_parser.bytes = n;
-                
-                if n == 1 && buffer[0] == 0x1B {
-                    key_parser.keyEvents.insert(KeyCode::Escape, true);
-                } else {
-                    parser.advance(&mut key_parser, &buffer[..n]);
-                }
+            match event {
+                Event::Input(bytes) => {
+                    key_parser.ParseBytes(&mut parser, &bytes);
+                    if !key_parser.response.is_empty() {
+                        stdout.write_all(&key_parser.response)?;
+                        stdout.flush()?;
+                    }
+                },
+                Event::Resize(size) => self.resize(size),
+                Event::Tick => {},
+                Event::Closed => break,
             }
             // control + c  ends the program
             if key_parser.ContainsModifier(&KeyModifiers::Control) && key_parser.ContainsChar('c') {  break;  }
             
-            self.handle_events(&key_parser).await;
-            self.render_logs().await;
-            self.render_log_creation().await;
-            self.renderer.Render(Some((self.area.width, self.area.height)));
+            self.update(&key_parser).await;
+            // a save that failed ends it (handed back below)
+            if self.save_error.is_some() {  break;  }
+            // a scroll keeps going for a few frames after the wheel stops
+            if key_parser.IsSettling() {
+                events.tick_after(SETTLE_TICK);
+            }
+        }
+        if key_parser.keyboardProtocol == KeyboardProtocol::Kitty {
+            disableKeyboardEnhancement(&mut stdout);
         }
         self.save();
         
-        Ok(())
+        match self.save_error.take() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+    
+    fn resize(&mut self, size: (u16, u16)) {
+        self.area = TermRender::Rect {
+            width: size.0,
+            height: size.1,
+        };
+        self.renderer.UpdateArea(size);
+    }
+    
+    // a single frame: handling the events and drawing everything
+    async fn update(&mut self, key_parser: &KeyParser) {
+        self.handle_events(key_parser).await;
+        self.render_logs().await;
+        self.render_log_creation().await;
+        self.render_habits().await;
+        self.render_analysis().await;
+        self.renderer.Render(Some((self.area.width, self.area.height)));
     }
     
     async fn handle_events(&mut self, key_parser: &KeyParser) {
         self.handle_mouse_events(key_parser).await;
+        if key_parser.ContainsModifier(&KeyModifiers::Control) && key_parser.ContainsChar('t') {
+            self.next_theme();
+        }
+        if self.creator_button.is_none() && self.renderer.ContainsWindow(String::from("LogView")) {
+            let log = self.renderer.GetWindowReferenceMut(String::from("LogView"));
+            // a page is the log's height (inside the border), less a line so there's some overlap
+            let page = log.GetViewportSize().1.saturating_sub(1).max(1) as i32;
+            if key_parser.ContainsKeyCode(KeyCode::PageUp) {
+                log.ScrollBy((0, -page));
+            }
+            if key_parser.ContainsKeyCode(KeyCode::PageDown) {
+                log.ScrollBy((0, page));
+            }
+        }
         if let Some(button) = &mut self.creator_button {
-            button.handle_events(key_parser);
+            button.handle_events(key_parser, &self.area, &mut self.logs, self.editing_index);
             if button.dead {
+                // the entry may have moved when it was sorted in, so the selection follows it
+                if let Some(saved_index) = button.saved_index {
+                    self.selected = match (self.editing_index, self.selected) {
+                        (Some(_), _) => Some(saved_index),
+                        (None, Some(selected)) if saved_index <= selected => Some(selected + 1),
+                        (None, selected) => selected,
+                    };
+                }
                 let _ = self.renderer.RemoveWindow(String::from("CreatorMenu"));
                 self.creator_button = None;
                 self.save();  // making sure, if it did create a new entry, it is saved (any ways, saving such a small set of data isn't even that slow)
                 self.editing_index = None;  // making sure it isn't still editing if it happened to be editing
             }
         }
+        if let Some(tracker) = &mut self.habit_tracker {
+            let changed = tracker.handle_events(key_parser, &self.area, &self.config.habits, &mut self.habit_log);
+            let dead = tracker.dead;
+            if changed {
+                self.save_habits();
+            }
+            if dead {
+                self.close_habit_tracker();
+            }
+        }
+        if let Some(analysis) = &mut self.analysis {
+            analysis.handle_events(key_parser);
+            if analysis.dead {
+                self.close_analysis();
+            } else if self.renderer.ContainsWindow(String::from("Analysis")) {
+                let window = self.renderer.GetWindowReferenceMut(String::from("Analysis"));
+                if key_parser.ContainsKeyCode(KeyCode::Up) {
+                    window.ScrollBy((0, -1));
+                }
+                if key_parser.ContainsKeyCode(KeyCode::Down) {
+                    window.ScrollBy((0, 1));
+                }
+            }
+        }
+    }
+    
+    // switches to the next theme, rebuilding every window in its colors on the next frame
+    fn next_theme(&mut self) {
+        self.theme = themes::next(&self.theme);
+        let names: Vec<String> = self.renderer.GetWindowNames().into_iter().cloned().collect();
+        for name in names {
+            let _ = self.renderer.RemoveWindow(name);
+        }
+    }
+    
+    // a new window wherever the screen layout puts it, which stays there as the terminal is resized
+    // (it still has to be added, under the same name)
+    fn new_placed_window(&mut self, name: &str, depth: u16, place: fn(&ScreenLayout) -> Area) -> TermRender::Window {
+        let area = place(&ScreenLayout::new(&self.area));
+        self.renderer.PlaceWindow(String::from(name), move |area| place(&ScreenLayout::new(area)));
+        TermRender::Window::new(area.position, depth, area.size)
+    }
+    
+    fn close_habit_tracker(&mut self) {
+        if self.habit_tracker.take().is_some() {
+            let _ = self.renderer.RemoveWindow(String::from("HabitTracker"));
+        }
+    }
+    
+    fn close_analysis(&mut self) {
+        if self.analysis.take().is_some() {
+            let _ = self.renderer.RemoveWindow(String::from("Analysis"));
+        }
     }
     
     async fn handle_mouse_events(&mut self, key_parser: &KeyParser) {
-        if let Some(event) = &key_parser.mouseEvent {
-            if event.eventType == MouseEventType::Left {
-                // checking the bounds
-                if self.creator_button.is_some() && event.position.0 > 25 && event.position.1 > 5 &&
-                    event.position.0 < self.area.width - 25 && event.position.1 < self.area.height - 5 {
-                    if event.state == MouseState::Release {
-                        self.creator_button.as_mut().unwrap().handle_mouse_events_for_creator(key_parser, event, &self.area, &mut self.logs, self.editing_index);
-                    } else {
-                        self.creator_button.as_mut().unwrap().handle_held_mouse(key_parser, event, &self.area, &mut self.logs, self.editing_index);
-                    }
-                } else if event.position.0 < 50 {
-                    if event.state != MouseState::Release {  return;  }
-                    // getting the height
-                    let index = event.position.1 as usize / 2 + self.scrolled - 1;
-                    if index < self.logs.len() {
-                        if self.creator_button.is_some() && self.renderer.ContainsWindow(String::from("CreatorMenu")) {
-                            // to make sure no weird overlapping happens
-                            self.renderer.GetWindowReferenceMut(String::from("CreatorMenu")).UpdateAll();
-                        }
-                        if self.selected.is_some() && self.selected.as_ref().unwrap() == &index {
-                            self.selected = None;
-                            // removing the render window for the log
-                            let _ = self.renderer.RemoveWindow(String::from("LogView"));
-                            let _ = self.renderer.RemoveWindow(String::from("EditButton"));
-                            let _ = self.renderer.RemoveWindow(String::from("DelButton"));
-                        } else {
-                            if self.selected.is_some() {
-                                self.renderer.GetWindowReferenceMut(String::from("EditButton")).UpdateAll();  // so it isn't clipped in half
-                                self.renderer.GetWindowReferenceMut(String::from("DelButton")).UpdateAll();  // so it isn't clipped in half
-                                self.renderer.GetWindowReferenceMut(String::from("Create")).UpdateAll();  // so it isn't clipped in half
-                            }
-                            self.selected = Some(index);
-                        }
-                    }
-                } else if event.state != MouseState::Release {
-                    return;  // only care about clicks, not releases outside the menu and scrolling in log list
-                } else if event.position.0 >= self.area.width - 16 && event.position.0 < self.area.width &&
-                          event.position.1 >= self.area.height - 3 && event.position.1 < self.area.height && self.editing_index.is_none() {
-                    // opening the creation menu
-                    if self.creator_button.is_none() {
-                        self.creator_button = Some(CreatorButton::new());
-                        self.editing_index = None;  // not editing rn
-                    } else {
-                        let _ = self.renderer.RemoveWindow(String::from("CreatorMenu"));
-                        self.creator_button = None;
-                    }
-                } else if event.position.0 >= self.area.width - 11 && event.position.1 <= self.area.width - 2 &&
-                          event.position.1 > 1 && event.position.1 < 5 && self.creator_button.is_none() &&
-                          self.renderer.ContainsWindow(String::from("EditButton")) {
-                    // editing the tab    unwrapping should be safe because the edit button is only created when a menu is open
-                    if self.creator_button.is_none() {
-                        self.editing_index = Some(self.selected.unwrap());
-                        
-                        let mut button = CreatorButton::new();
-                        let log = &self.logs[self.selected.unwrap()];
-                        button.events = log.events.clone().unwrap_or(vec![]);
-                        button.title = log.entry_title.clone();
-                        button.text = log.entry_text.clone();
-                        button.events = log.events.as_ref().unwrap_or(&vec![]).clone();
-                        button.food = log.food.as_ref().unwrap_or(&vec![]).clone();
-                        button.mood_quality = log.mood.as_ref().map_or(5, |m| m.quality);
-                        button.mood_description = log.mood.as_ref().map_or(String::new(), |m| m.description.clone());
-                        button.mood_reason = log.mood.as_ref().and_then(|m| m.reason.clone()).unwrap_or(String::new());
-                        button.update_cursors();
-                        
-                        self.creator_button = Some(button);
-                    } else {
-                        let _ = self.renderer.RemoveWindow(String::from("CreatorMenu"));
-                        self.creator_button = None;
-                    }
-                } else if event.position.0 >= self.area.width - 22 && event.position.1 <= self.area.width - 13 &&
-                    event.position.1 > 1 && event.position.1 < 5 && self.creator_button.is_none() &&
-                    self.renderer.ContainsWindow(String::from("DelButton")) && self.creator_button.is_none() {
-                    // deleting the log
-                    if self.selected.is_some() {
-                        self.logs.remove(self.selected.unwrap());
-                        self.selected = None;
-                        let _ = self.renderer.RemoveWindow(String::from("LogView"));
-                        let _ = self.renderer.RemoveWindow(String::from("EditButton"));
-                        let _ = self.renderer.RemoveWindow(String::from("DelButton"));
-                        self.scrolled = self.scrolled.saturating_sub(1);
-                        self.save();  // saving the result
+        let Some((position, action)) = get_mouse_action(key_parser) else {  return;  };
+        // scrollable windows (like the list of logs) scroll themselves
+        if let TermRender::MouseAction::Scroll(lines) = action && self.renderer.ScrollAt(position, (0, lines)) {  return;  }
+        if let Some((handler, hit)) = self.mouse_router.Route(&self.renderer, position) {
+            handler(self, &hit, action);
+        }
+    }
+    
+    // every window that can be clicked, and what happens when it is
+    fn new_mouse_router() -> TermRender::MouseRouter<App> {
+        let mut router = TermRender::MouseRouter::default();
+        router.On(String::from("Logs"), App::on_logs_mouse);
+        router.On(String::from("EditButton"), App::on_edit_button_mouse);
+        router.On(String::from("DelButton"), App::on_delete_button_mouse);
+        router.On(String::from("Create"), App::on_create_button_mouse);
+        router.On(String::from("Habits"), App::on_habits_button_mouse);
+        router.On(String::from("AnalysisButton"), App::on_analysis_button_mouse);
+        router.On(String::from("CreatorMenu"), App::on_creator_mouse);
+        router.On(String::from("Suggestions"), App::on_suggestions_mouse);
+        router.On(String::from("HabitTracker"), App::on_habit_tracker_mouse);
+        router.On(String::from("Analysis"), App::on_analysis_mouse);
+        router
+    }
+    
+    fn on_logs_mouse(&mut self, hit: &TermRender::MouseHit, action: TermRender::MouseAction) {
+        match action {
+            TermRender::MouseAction::Click if !hit.onBorder => {
+                // each log takes up two lines (the title and date)
+                let index = hit.line / 2;
+                if index >= self.logs.len() {  return;  }
+                if self.selected.is_some() && self.selected.as_ref().unwrap() == &index {
+                    self.selected = None;
+                    // removing the render window for the log
+                    let _ = self.renderer.RemoveWindow(String::from("LogView"));
+                    let _ = self.renderer.RemoveWindow(String::from("EditButton"));
+                    let _ = self.renderer.RemoveWindow(String::from("DelButton"));
+                } else {
+                    self.selected = Some(index);
+                    if self.renderer.ContainsWindow(String::from("LogView")) {
+                        self.renderer.GetWindowReferenceMut(String::from("LogView")).ScrollTo((0, 0));
                     }
                 }
-            } else if event.position.0 < 50 {
-                // checking for scrolling
-                if event.eventType == MouseEventType::Down {
-                    self.scrolled = usize::min(
-                        self.scrolled + (key_parser.scrollAccumulate * 4.) as usize,
-                        self.logs.len().saturating_sub(1)
-                    );
-                }
-                if event.eventType == MouseEventType::Up {
-                    self.scrolled = self.scrolled.saturating_sub((key_parser.scrollAccumulate * -4.) as usize);
-                }
-            }
+            },
+            _ => {},
+        }
+    }
+    
+    fn on_create_button_mouse(&mut self, _hit: &TermRender::MouseHit, action: TermRender::MouseAction) {
+        if action != TermRender::MouseAction::Click || self.editing_index.is_some() {  return;  }
+        // opening the creation menu
+        if self.creator_button.is_none() {
+            self.close_habit_tracker();
+            self.close_analysis();
+            self.creator_button = Some(CreatorButton::new(&self.config, &self.logs));
+            self.editing_index = None;  // not editing rn
+        } else {
+            let _ = self.renderer.RemoveWindow(String::from("CreatorMenu"));
+            self.creator_button = None;
+        }
+    }
+    
+    fn on_habits_button_mouse(&mut self, _hit: &TermRender::MouseHit, action: TermRender::MouseAction) {
+        if action != TermRender::MouseAction::Click || self.creator_button.is_some() {  return;  }
+        // opening the habit tracker
+        if self.habit_tracker.is_none() {
+            self.close_analysis();
+            self.habit_tracker = Some(HabitTracker::new(&self.area));
+        } else {
+            self.close_habit_tracker();
+        }
+    }
+    
+    fn on_analysis_button_mouse(&mut self, _hit: &TermRender::MouseHit, action: TermRender::MouseAction) {
+        if action != TermRender::MouseAction::Click || self.creator_button.is_some() {  return;  }
+        // opening the mood analysis
+        if self.analysis.is_none() {
+            self.close_habit_tracker();
+            self.analysis = Some(AnalysisView::new());
+        } else {
+            self.close_analysis();
+        }
+    }
+    
+    fn on_edit_button_mouse(&mut self, _hit: &TermRender::MouseHit, action: TermRender::MouseAction) {
+        if action != TermRender::MouseAction::Click || self.creator_button.is_some() {  return;  }
+        // editing the tab    unwrapping should be safe because the edit button is only created when a menu is open
+        self.close_habit_tracker();
+        self.close_analysis();
+        self.editing_index = Some(self.selected.unwrap());
+        
+        let mut button = CreatorButton::new(&self.config, &self.logs);
+        let log = &self.logs[self.selected.unwrap()];
+        button.title.SetText(log.entry_title.clone());
+        button.text.SetText(log.entry_text.clone());
+        button.events = CreatorButton::new_list(log.events.clone().unwrap_or_default());
+        button.food = CreatorButton::new_list(log.food.clone().unwrap_or_default());
+        button.mood_quality.SetValue(log.mood.as_ref().map_or(5, |m| m.quality as i32));
+        button.mood_description.SetText(log.mood.as_ref().map_or(String::new(), |m| m.description.clone()));
+        button.mood_reason.SetText(log.mood.as_ref().and_then(|m| m.reason.clone()).unwrap_or_default());
+        if let Some(mood) = &log.mood {
+            button.set_dimension_values(mood);
+        }
+        button.set_custom_values(log.custom_fields.as_ref().unwrap_or(&vec![]));
+        if let Some(time) = log.get_local_time() {
+            button.entry_time = time;
+        }
+        if log.entry_time.is_none() {
+            button.kept_date = Some(log.entry_date.clone());
+        }
+        
+        self.creator_button = Some(button);
+    }
+    
+    fn on_delete_button_mouse(&mut self, _hit: &TermRender::MouseHit, action: TermRender::MouseAction) {
+        if action != TermRender::MouseAction::Click || self.creator_button.is_some() {  return;  }
+        // deleting the log
+        if let Some(selected) = self.selected {
+            self.logs.remove(selected);
+            self.selected = None;
+            let _ = self.renderer.RemoveWindow(String::from("LogView"));
+            let _ = self.renderer.RemoveWindow(String::from("EditButton"));
+            let _ = self.renderer.RemoveWindow(String::from("DelButton"));
+            self.save();  // saving the result
+        }
+    }
+    
+    fn on_creator_mouse(&mut self, hit: &TermRender::MouseHit, action: TermRender::MouseAction) {
+        let Some(button) = &mut self.creator_button else {  return;  };
+        if hit.onBorder {  return;  }
+        match action {
+            TermRender::MouseAction::Press | TermRender::MouseAction::Drag | TermRender::MouseAction::Click => {
+                button.handle_mouse_events(hit, action, &self.area, &mut self.logs, self.editing_index);
+            },
+            _ => {},
+        }
+    }
+    
+    fn on_suggestions_mouse(&mut self, hit: &TermRender::MouseHit, action: TermRender::MouseAction) {
+        if action != TermRender::MouseAction::Click || hit.onBorder {  return;  }
+        if let Some(button) = &mut self.creator_button {
+            button.click_suggestion(hit.line, &self.area);
+        }
+    }
+    
+    fn on_habit_tracker_mouse(&mut self, hit: &TermRender::MouseHit, action: TermRender::MouseAction) {
+        let Some(tracker) = &mut self.habit_tracker else {  return;  };
+        let changed = match action {
+            TermRender::MouseAction::Click => tracker.handle_mouse_events(hit, &self.area, &self.config.habits, &mut self.habit_log, false),
+            // right clicking takes a check off back
+            TermRender::MouseAction::RightClick => tracker.handle_mouse_events(hit, &self.area, &self.config.habits, &mut self.habit_log, true),
+            TermRender::MouseAction::Hover => {
+                tracker.hover(hit, &self.area, &self.config.habits);
+                false
+            },
+            _ => false,
+        };
+        if changed {
+            self.save_habits();
+        }
+    }
+    
+    fn on_analysis_mouse(&mut self, hit: &TermRender::MouseHit, action: TermRender::MouseAction) {
+        let Some(analysis) = &mut self.analysis else {  return;  };
+        if action == TermRender::MouseAction::Click {
+            analysis.handle_mouse_events(hit);
         }
     }
     
     async fn render_log_creation(&mut self) {
+        let theme = Theme::Current();
         // rendering the create log button
-        if self.renderer.ContainsWindow(String::from("Create")) {
-            let button = self.renderer.GetWindowReferenceMut(String::from("Create"));
-            button.Resize((15, 3));
-            button.Move((self.area.width - 16, self.area.height - 3))
-        } else {
-            let mut window = TermRender::Window::new((self.area.width - 16, self.area.height - 3), 1, (15, 3));
-            window.AddLine(TermRender::Span::FromTokens(vec!["  New Entry    ".Colorizes(vec![TermRender::ColorType::White, TermRender::ColorType::OnBrightBlack])]));
+        if !self.renderer.ContainsWindow(String::from("Create")) {
+            let mut window = self.new_placed_window("Create", 1, |layout| layout.create_button);
+            window.AddLine(TermRender::Span::FromTokens(vec![format!("{:^13}", tr("new_entry")).Stylize(&theme.button)]));
             window.Bordered();
-            window.Colorize(TermRender::ColorType::OnBrightBlack);
+            window.Colorizes(theme.button.clone());
             self.renderer.AddWindow(window, String::from("Create"), vec![])
         }
         
         // rendering the creator button
-        if let Some(button) = &self.creator_button {
+        if let Some(button) = &mut self.creator_button {
+            button.resize(&self.area);
+            let text = button.get_window_text(&self.area);
             if self.renderer.ContainsWindow(String::from("CreatorMenu")) {
                 let button_renderer = self.renderer.GetWindowReferenceMut(String::from("CreatorMenu"));
-                button_renderer.Resize((self.area.width - 50, self.area.height - 10));
-                button_renderer.TryUpdateLines(button.get_window_text(&self.area));
+                button_renderer.TryUpdateLines(text);
             } else {
-                let mut window = TermRender::Window::new((25, 5), 2, (self.area.width - 50, self.area.height - 10));
+                let mut window = self.new_placed_window("CreatorMenu", 2, |layout| layout.popup);
                 window.Bordered();
-                window.Colorize(TermRender::ColorType::Bold);
+                window.Colorizes(theme.border.clone());
                 let create_text = match self.editing_index {
-                    Some(_) => " Editing Entry ",
-                    None =>    "Create New Entry"
+                    Some(_) => format!(" {} ", tr("editing_entry")),
+                    None =>    tr("create_new_entry").to_string(),
                 };
                 window.TitledColored(TermRender::Span::FromTokens(vec![
-                    create_text.Colorizes(vec![TermRender::ColorType::Bold, TermRender::ColorType::BrightWhite])
+                    create_text.Stylize(&theme.title)
                 ]));
-                window.FromLines(button.get_window_text(&self.area));
+                window.FromLines(text);
                 self.renderer.AddWindow(window, String::from("CreatorMenu"), vec![String::from("Pop Up")]);
             }
         }
+        
+        // the dropdown of suggestions for the list item being edited
+        let suggestions = self.creator_button.as_ref().and_then(|button| button.get_suggestions(&self.area))
+            .map(|(position, suggestions)| (position, CreatorButton::get_suggestion_text(&suggestions)));
+        let Some((position, lines)) = suggestions else {
+            if self.renderer.ContainsWindow(String::from("Suggestions")) {
+                let _ = self.renderer.RemoveWindow(String::from("Suggestions"));
+            }
+            return;
+        };
+        let size = CreatorButton::get_suggestion_size(&lines);
+        if self.renderer.ContainsWindow(String::from("Suggestions")) {
+            let window = self.renderer.GetWindowReferenceMut(String::from("Suggestions"));
+            window.Move(position);
+            window.Resize(size);
+            window.TryUpdateLines(lines);
+        } else {
+            let mut window = TermRender::Window::new(position, 3, size);
+            window.Bordered();
+            window.FromLines(lines);
+            self.renderer.AddWindow(window, String::from("Suggestions"), vec![String::from("Pop Up")]);
+        }
+    }
+    
+    async fn render_habits(&mut self) {
+        let theme = Theme::Current();
+        // rendering the button to open the tracker (next to the new entry button)
+        if !self.renderer.ContainsWindow(String::from("Habits")) {
+            let mut window = self.new_placed_window("Habits", 1, |layout| layout.habits_button);
+            window.AddLine(TermRender::Span::FromTokens(vec![format!("{:^13}", tr("habits")).Stylize(&theme.button)]));
+            window.Bordered();
+            window.Colorizes(theme.button.clone());
+            self.renderer.AddWindow(window, String::from("Habits"), vec![])
+        }
+        
+        let Some(tracker) = &self.habit_tracker else {  return;  };
+        let text = tracker.get_window_text(&self.area, &self.config.habits, &self.habit_log);
+        if self.renderer.ContainsWindow(String::from("HabitTracker")) {
+            let window = self.renderer.GetWindowReferenceMut(String::from("HabitTracker"));
+            window.TryUpdateLines(text);
+        } else {
+            let mut window = self.new_placed_window("HabitTracker", 2, |layout| layout.popup);
+            window.Bordered();
+            window.Colorizes(theme.border.clone());
+            window.TitledColored(TermRender::Span::FromTokens(vec![
+                format!(" {} ", tr("habits")).Stylize(&theme.title)
+            ]));
+            window.FromLines(text);
+            self.renderer.AddWindow(window, String::from("HabitTracker"), vec![String::from("Pop Up")]);
+        }
+    }
+    
+    async fn render_analysis(&mut self) {
+        let theme = Theme::Current();
+        // rendering the button to open the analysis (next to the habits button)
+        if !self.renderer.ContainsWindow(String::from("AnalysisButton")) {
+            let mut window = self.new_placed_window("AnalysisButton", 1, |layout| layout.analysis_button);
+            window.AddLine(TermRender::Span::FromTokens(vec![format!("{:^13}", tr("analysis")).Stylize(&theme.button)]));
+            window.Bordered();
+            window.Colorizes(theme.button.clone());
+            self.renderer.AddWindow(window, String::from("AnalysisButton"), vec![])
+        }
+        
+        let Some(analysis) = &mut self.analysis else {  return;  };
+        let text = analysis.get_window_text(&self.logs);
+        if self.renderer.ContainsWindow(String::from("Analysis")) {
+            let window = self.renderer.GetWindowReferenceMut(String::from("Analysis"));
+            window.TryUpdateLines(text);
+        } else {
+            let mut window = self.new_placed_window("Analysis", 2, |layout| layout.popup);
+            window.Bordered();
+            window.Scrollbar();
+            window.Colorizes(theme.border.clone());
+            window.TitledColored(TermRender::Span::FromTokens(vec![
+                format!(" {} ", tr("mood_analysis")).Stylize(&theme.title)
+            ]));
+            window.FromLines(text);
+            self.renderer.AddWindow(window, String::from("Analysis"), vec![String::from("Pop Up")]);
+        }
+    }
+    
+    // the open log, wrapped to the window (inside the border)
+    fn get_log_view_text(&self) -> Vec<TermRender::Span> {
+        let width = (ScreenLayout::new(&self.area).log_view.size.0 as usize).saturating_sub(2);
+        self.logs[*self.selected.as_ref().unwrap()].get_render_text(&self.config)
+            .iter().flat_map(|line| line.Wrap(width)).collect()
+    }
+    
+    // a title showing which lines of the open log are in view, if they don't all fit
+    fn get_scroll_indicator(window: &TermRender::Window) -> TermRender::Span {
+        let theme = Theme::Current();
+        let (_, scroll) = window.GetScroll();
+        let (_, height) = window.GetViewportSize();
+        let (_, length) = window.GetContentSize();
+        match length > height {
+            true => TermRender::Span::FromTokens(vec![
+                format!(" {}-{}/{} ", scroll + 1, usize::min(scroll + height, length), length)
+                    .Stylize(&theme.muted)
+            ]),
+            false => TermRender::Span::default(),
+        }
     }
     
     async fn render_logs(&mut self) {
+        let theme = Theme::Current();
         let mut render = vec![];
-        let start_index = self.scrolled;
-        for index in start_index..self.logs.len() {
+        // the lines are padded out to the width of the list (inside the border) so the selection fills it
+        let width = (ScreenLayout::new(&self.area).logs.size.0 as usize).saturating_sub(2);
+        let padding = |used: usize| " ".repeat(width.saturating_sub(used)).Colorizes(vec![]);
+        for index in 0..self.logs.len() {
             let log = &self.logs[index];
             // printing the title and date
-            let mut tokens = vec![" ".Colorizes(vec![]), log.get_title().Colorizes(vec![
-                TermRender::ColorType::BrightWhite, TermRender::ColorType::Bold
-            ]), "                                                                      ".Colorizes(vec![])];
+            let title = log.get_title();
+            let mut tokens = vec![" ".Colorizes(vec![]), title.Stylize(&theme.strong), padding(1 + TermRender::GetDisplayWidth(&title))];
             if self.selected.as_ref().unwrap_or(&usize::MAX) == &index {
                 for token in tokens.iter_mut(){
-                    token.AddColor(TermRender::ColorType::OnBrightBlack);
+                    for color in &theme.selection {
+                        token.AddColor(*color);
+                    }
                 }
             }
             render.push(TermRender::Span::FromTokens(tokens));
-            let mut tokens = vec!["    ".Colorizes(vec![]), log.get_date().Colorizes(vec![
-                TermRender::ColorType::White, TermRender::ColorType::Italic
-            ]), "                                                                      ".Colorizes(vec![])];
+            let date = log.get_date();
+            let mut tokens = vec!["    ".Colorizes(vec![]), date.Stylize(&theme.label), padding(4 + TermRender::GetDisplayWidth(&date))];
             if self.selected.as_ref().unwrap_or(&usize::MAX) == &index {
                 for token in tokens.iter_mut(){
-                    token.AddColor(TermRender::ColorType::OnBrightBlack);
+                    for color in &theme.selection {
+                        token.AddColor(*color);
+                    }
                 }
             }
             render.push(TermRender::Span::FromTokens(tokens));
@@ -287,115 +606,465 @@ impl App {
         
         if self.renderer.ContainsWindow(String::from("Logs")) {
             let logs = self.renderer.GetWindowReferenceMut(String::from("Logs"));
-            logs.Resize((50, self.area.height));
             logs.TryUpdateLines(render);
         } else {
-            let mut window = TermRender::Window::new((1, 1), 0, (50, self.area.height));
-            window.FromLines(render);
+            let mut window = self.new_placed_window("Logs", 0, |layout| layout.logs);
             window.Bordered();
+            window.Scrollbar();
+            window.FromLines(render);
+            // starting on the newest entries
+            window.ScrollTo((0, usize::MAX));
             self.renderer.AddWindow(window, String::from("Logs"), vec![]);
         }
         
         // rendering the actual log if one is open
         if self.selected.is_none() {  return;  }
+        let log_text = self.get_log_view_text();
         if self.renderer.ContainsWindow(String::from("LogView")) {
             let log = self.renderer.GetWindowReferenceMut(String::from("LogView"));
-            log.TryUpdateLines(self.logs[*self.selected.as_ref().unwrap()].get_render_text());
-            log.Resize((self.area.width - 49, self.area.height));
-            
-            // adding the edit button     String::from("EditButton")
-            let log = self.renderer.GetWindowReferenceMut(String::from("EditButton"));
-            log.Move((self.area.width - 11, 2));
-            
-            let log = self.renderer.GetWindowReferenceMut(String::from("DelButton"));
-            log.Move((self.area.width - 22, 2));
+            log.TryUpdateLines(log_text);
+            let scroll_indicator = Self::get_scroll_indicator(log);
+            log.TitledColored(scroll_indicator);
         } else {
-            let mut window = TermRender::Window::new((50, 1), 0, (self.area.width - 49, self.area.height));
+            let mut window = self.new_placed_window("LogView", 0, |layout| layout.log_view);
             window.Bordered();
+            window.Scrollbar();
             // adding the text
-            window.FromLines(self.logs[*self.selected.as_ref().unwrap()].get_render_text());
+            window.FromLines(log_text);
+            window.TitledColored(Self::get_scroll_indicator(&window));
             self.renderer.AddWindow(window, String::from("LogView"), vec![]);
             
             // adding the edit button     String::from("EditButton")
-            let mut window = TermRender::Window::new((self.area.width - 11, 2), 1, (10, 3));
+            let mut window = self.new_placed_window("EditButton", 1, |layout| layout.edit_button);
             window.Bordered();
             window.AddLine(TermRender::Span::FromTokens(vec![
-                "  Edit  ".Colorizes(vec![TermRender::ColorType::Bold, TermRender::ColorType::BrightWhite])
+                format!("{:^8}", tr("edit")).Stylize(&theme.strong)
             ]));
             self.renderer.AddWindow(window, String::from("EditButton"), vec![]);
             
-            let mut window = TermRender::Window::new((self.area.width - 22, 2), 1, (10, 3));
+            let mut window = self.new_placed_window("DelButton", 1, |layout| layout.delete_button);
             window.Bordered();
             window.AddLine(TermRender::Span::FromTokens(vec![
-                " Delete ".Colorizes(vec![TermRender::ColorType::Bold, TermRender::ColorType::BrightWhite])
+                format!("{:^8}", tr("delete")).Stylize(&theme.strong)
             ]));
             self.renderer.AddWindow(window, String::from("DelButton"), vec![]);
+        }
+    }
+}
 
-            // updating the creation button
-            self.renderer.GetWindowReferenceMut(String::from("Create")).UpdateAll();
-            if self.creator_button.is_some() {
-                self.renderer.GetWindowReferenceMut(String::from("CreatorMenu")).UpdateAll();
-            }
+// what the mouse did this frame and where, if anything
+// a scroll stays on the event for a few frames, easing off as the accumulated scroll does
+fn get_mouse_action(key_parser: &KeyParser) -> Option<((u16, u16), TermRender::MouseAction)> {
+    let event = key_parser.mouseEvent.as_ref()?;
+    let action = match (&event.eventType, &event.state) {
+        (MouseEventType::Left, MouseState::Press) => TermRender::MouseAction::Press,
+        (MouseEventType::Left, MouseState::Hold) => TermRender::MouseAction::Drag,
+        (MouseEventType::Left, MouseState::Release) => TermRender::MouseAction::Click,
+        (MouseEventType::Right, MouseState::Release) => TermRender::MouseAction::RightClick,
+        // only the frame the movement came in, or it would keep undoing keyboard movement
+        (MouseEventType::Null, MouseState::Press) => TermRender::MouseAction::Hover,
+        (MouseEventType::Down | MouseEventType::Up, _) => TermRender::MouseAction::Scroll((key_parser.scrollAccumulate * 4.) as i32),
+        _ => return None,
+    };
+    Some((event.position, action))
+}
+
+// where each window goes, worked out from the size of the terminal
+struct ScreenLayout {
+    logs: Area,
+    log_view: Area,
+    edit_button: Area,
+    delete_button: Area,
+    analysis_button: Area,
+    habits_button: Area,
+    create_button: Area,
+    // the editor, habit tracker and analysis all open in the middle of the screen
+    popup: Area,
+}
+
+impl ScreenLayout {
+    fn new(area: &TermRender::Rect) -> Self {
+        use Constraint::{Fill, Fixed};
+        let screen = Area::FromRect(area);
+        // the list of logs shares its border with the open log
+        let [logs, log_view] = Layout::Columns(vec![Fixed(50), Fill(1)]).Overlap(1).Areas(screen);
+        
+        // the edit and delete buttons sit in the top right of the open log
+        let [_, top_row, _] = Layout::Rows(vec![Fixed(1), Fixed(3), Fill(1)]).Areas(log_view);
+        let [_, delete_button, _, edit_button, _] = Layout::Columns(vec![
+            Fill(1), Fixed(10), Fixed(1), Fixed(10), Fixed(2),
+        ]).Areas(top_row);
+        
+        // and the buttons for the menus along the bottom right
+        let [_, bottom_row, _] = Layout::Rows(vec![Fill(1), Fixed(3), Fixed(1)]).Areas(screen);
+        let [_, analysis_button, _, habits_button, _, create_button, _] = Layout::Columns(vec![
+            Fill(1), Fixed(15), Fixed(1), Fixed(15), Fixed(1), Fixed(15), Fixed(2),
+        ]).Areas(bottom_row);
+        
+        let [_, popup_columns, _] = Layout::Columns(vec![Fixed(24), Fill(1), Fixed(26)]).Areas(screen);
+        let [_, popup, _] = Layout::Rows(vec![Fixed(4), Fill(1), Fixed(6)]).Areas(popup_columns);
+        
+        ScreenLayout {
+            logs,
+            log_view,
+            edit_button,
+            delete_button,
+            analysis_button,
+            habits_button,
+            create_button,
+            popup,
         }
     }
+    
+    // the space for text inside the popup's border, which the menus lay themselves out in
+    fn popup_text(&self) -> Area {
+        self.popup.Inner(1)
+    }
+}
+
+// where things go in the entry editor's text, counting from inside the menu's border
+struct FormLayout {
+    // the width everything is centered in
+    width: usize,
+    // the number of lines, the last three of which hold the date picker and create button
+    height: usize,
+    // the create button sits in the bottom right corner, a column in from the border
+    button_column: usize,
 }
 
-#[derive(PartialEq, Eq)]
+// how many lines tall the entry text box is, and the widest it gets (in characters)
+const TEXT_AREA_HEIGHT: usize = 4;
+const TEXT_AREA_MAX_WIDTH: usize = 60;
+// the most suggestions shown under a list item at once
+const MAX_SUGGESTIONS: usize = 5;
+// the line (within the window) of the add events heading; everything below it is laid out from here
+const EVENTS_LINE: usize = 6 + TEXT_AREA_HEIGHT;
+// the columns (within the window) of each part of the date picker:
+// the [-] button, the year, month, day, hour and minute, then the [+] button
+const DATE_PICKER_SPANS: [std::ops::Range<usize>; 7] = [2..5, 6..10, 11..13, 14..16, 17..19, 20..22, 23..26];
+
+#[derive(PartialEq, Eq, Clone, Copy)]
 enum CreationField {
     Title,
     Text,
     Events,
     Foods,
+    Mood,
+    Dimension(usize),
     MoodDescription,
     MoodReason,
+    Custom(usize),
+    Date,
+    Create,
+}
+
+// the editor's state for one of the user's custom fields
+// numbers are kept as typed until the entry is saved, so half typed ones like "7." aren't lost
+enum CustomInput {
+    Text(widgets::TextInput),
+    Number(widgets::TextInput),
+    Boolean(bool),
+    List(widgets::ListInput),
+    Rating(widgets::Slider),
+}
+
+impl CustomInput {
+    fn new(field: &config::CustomField) -> Self {
+        match field.kind {
+            config::FieldKind::Text => CustomInput::Text(Self::new_text(String::new())),
+            config::FieldKind::Number => CustomInput::Number(Self::new_number(String::new())),
+            config::FieldKind::Boolean => CustomInput::Boolean(false),
+            config::FieldKind::List => CustomInput::List(CreatorButton::new_list(vec![])),
+            config::FieldKind::Rating => CustomInput::Rating(widgets::Slider::new((field.max.max(1) + 1) / 2, 1, field.max.max(1))),
+        }
+    }
+    
+    // None if the value doesn't match the field's kind (the config was changed since it was saved)
+    fn from_value(field: &config::CustomField, value: &entries::FieldValue) -> Option<Self> {
+        Some(match (field.kind, value) {
+            (config::FieldKind::Text, entries::FieldValue::Text(text)) => CustomInput::Text(Self::new_text(text.clone())),
+            (config::FieldKind::Number, entries::FieldValue::Number(number)) => CustomInput::Number(Self::new_number(format!("{}", number))),
+            (config::FieldKind::Boolean, entries::FieldValue::Boolean(value)) => CustomInput::Boolean(*value),
+            (config::FieldKind::List, entries::FieldValue::List(items)) => CustomInput::List(CreatorButton::new_list(items.clone())),
+            (config::FieldKind::Rating, entries::FieldValue::Rating(rating)) => CustomInput::Rating(widgets::Slider::new(*rating, 1, field.max.max(1))),
+            _ => return None,
+        })
+    }
+    
+    fn new_text(text: String) -> widgets::TextInput {
+        widgets::TextInput::new(text).Placeholder(tr("text_placeholder"))
+    }
+    
+    fn new_number(text: String) -> widgets::TextInput {
+        Self::new_text(text).Filter(|chr| chr.is_ascii_digit() || chr == '.' || chr == '-')
+    }
+    
+    // empty inputs aren't saved
+    fn get_value(&self) -> Option<entries::FieldValue> {
+        match self {
+            CustomInput::Text(text) if !text.GetText().is_empty() => Some(entries::FieldValue::Text(text.GetText().to_string())),
+            CustomInput::Number(text) => text.GetText().trim().parse().ok().map(entries::FieldValue::Number),
+            CustomInput::Boolean(value) => Some(entries::FieldValue::Boolean(*value)),
+            CustomInput::List(items) => {
+                let items: Vec<String> = items.GetItems().iter().filter(|item| !item.is_empty()).cloned().collect();
+                if items.is_empty() { None } else { Some(entries::FieldValue::List(items)) }
+            },
+            CustomInput::Rating(rating) => Some(entries::FieldValue::Rating(rating.GetValue())),
+            _ => None,
+        }
+    }
+    
+    // the widget editing the field (yes/no fields are just toggled, so don't have one)
+    fn get_widget(&self) -> Option<&dyn Widget> {
+        match self {
+            CustomInput::Text(text) | CustomInput::Number(text) => Some(text),
+            CustomInput::List(items) => Some(items),
+            CustomInput::Rating(rating) => Some(rating),
+            CustomInput::Boolean(_) => None,
+        }
+    }
+    
+    fn get_widget_mut(&mut self) -> Option<&mut dyn Widget> {
+        match self {
+            CustomInput::Text(text) | CustomInput::Number(text) => Some(text),
+            CustomInput::List(items) => Some(items),
+            CustomInput::Rating(rating) => Some(rating),
+            CustomInput::Boolean(_) => None,
+        }
+    }
+    
+    // the number of lines shown under the field's heading
+    fn get_height(&self) -> usize {
+        self.get_widget().map_or(1, |widget| widget.Height())
+    }
 }
 
 struct CreatorButton {
     selected_field: Option<CreationField>,
-    title: String,
-    text: String,
-    cursors: [usize; 6],
+    pub title: widgets::TextInput,
+    pub text: widgets::TextArea,
     pub dead: bool,
-    events: Vec<String>,
-    food: Vec<String>,
-    mood_quality: usize,
-    mood_description: String,
-    mood_reason: String,
+    pub events: widgets::ListInput,
+    pub food: widgets::ListInput,
+    // past items for autocompleting the lists (custom fields that aren't lists just have an empty one)
+    event_history: suggestions::History,
+    food_history: suggestions::History,
+    custom_histories: Vec<suggestions::History>,
+    pub mood_quality: widgets::Slider,
+    pub mood_description: widgets::TextInput,
+    pub mood_reason: widgets::TextInput,
+    mood_dimensions: Vec<config::MoodDimension>,
+    dimension_values: Vec<widgets::Slider>,
+    custom_fields: Vec<config::CustomField>,
+    custom_inputs: Vec<CustomInput>,
+    // an edited entry's ratings and values for dimensions/fields no longer in the config, saved back as they were
+    kept_dimensions: Vec<entries::MoodRating>,
+    kept_custom_values: Vec<entries::CustomValue>,
+    create_button: widgets::Button,
+    // when the entry was written, defaulting to now but can be moved back for catching up
+    pub entry_time: chrono::NaiveDateTime,
+    // an older entry's written out date (it has no time), which it keeps unless the date picker is changed
+    pub kept_date: Option<String>,
+    // the part of the date picker the arrows adjust (0 through 4: year, month, day, hour, minute)
+    date_segment: usize,
+    // where the entry ended up in the logs once it was created
+    pub saved_index: Option<usize>,
+    // the last suggestion taken, so the dropdown stays closed until the item is changed again
+    taken_suggestion: Option<String>,
 }
 
 impl CreatorButton {
-    pub fn new() -> Self {
+    pub fn new(config: &Config, logs: &Logs) -> Self {
         CreatorButton {
             selected_field: None,
-            title: String::new(),
-            text: String::new(),
-            cursors: [0usize; 6],
+            title: widgets::TextInput::new(String::new()).Placeholder(tr("title_placeholder")),
+            text: widgets::TextArea::new(String::new(), (1, TEXT_AREA_HEIGHT)).Placeholder(tr("text_placeholder")),
             dead: false,
-            events: vec![],
-            food: vec![],
-            mood_quality: 5,
-            mood_description: String::new(),
-            mood_reason: String::new(),
+            events: Self::new_list(vec![]),
+            food: Self::new_list(vec![]),
+            event_history: suggestions::History::events(logs),
+            food_history: suggestions::History::foods(logs),
+            custom_histories: config.custom_fields.iter().map(|field| match field.kind {
+                config::FieldKind::List => suggestions::History::custom_list(logs, &field.name),
+                _ => suggestions::History::default(),
+            }).collect(),
+            mood_quality: widgets::Slider::new(5, 1, 10),
+            mood_description: widgets::TextInput::new(String::new()).Placeholder(tr("text_placeholder")),
+            mood_reason: widgets::TextInput::new(String::new()).Placeholder(tr("text_placeholder")),
+            mood_dimensions: config.mood_dimensions.clone(),
+            dimension_values: config.mood_dimensions.iter().map(|dimension| {
+                widgets::Slider::new(dimension.middle(), dimension.min, dimension.max)
+            }).collect(),
+            custom_fields: config.custom_fields.clone(),
+            custom_inputs: config.custom_fields.iter().map(CustomInput::new).collect(),
+            kept_dimensions: vec![],
+            kept_custom_values: vec![],
+            create_button: widgets::Button::new(tr("create")),
+            entry_time: chrono::Local::now().naive_local(),
+            kept_date: None,
+            date_segment: 2,
+            saved_index: None,
+            taken_suggestion: None,
+        }
+    }
+    
+    // a list of events, foods or a list field's items
+    pub fn new_list(items: Vec<String>) -> widgets::ListInput {
+        widgets::ListInput::new(items).Placeholder(tr("text_placeholder"))
+    }
+    
+    pub fn set_dimension_values(&mut self, mood: &entries::Mood) {
+        for (dimension, value) in self.mood_dimensions.iter().zip(self.dimension_values.iter_mut()) {
+            if let Some(rated) = mood.get_dimension(&dimension.name) {
+                value.SetValue(rated);
+            }
+        }
+        self.kept_dimensions = mood.dimensions.iter().flatten()
+            .filter(|rating| !self.mood_dimensions.iter().any(|dimension| dimension.name == rating.name))
+            .cloned()
+            .collect();
+    }
+    
+    pub fn set_custom_values(&mut self, values: &[entries::CustomValue]) {
+        for (field, input) in self.custom_fields.iter().zip(self.custom_inputs.iter_mut()) {
+            let value = values.iter().find(|value| value.name == field.name);
+            if let Some(loaded) = value.and_then(|value| CustomInput::from_value(field, &value.value)) {
+                *input = loaded;
+            }
         }
+        self.kept_custom_values = values.iter()
+            .filter(|value| !self.custom_fields.iter().any(|field| field.name == value.name))
+            .cloned()
+            .collect();
     }
     
-    pub fn update_cursors(&mut self) {
-        self.cursors = [
-            self.title.len(),
-            self.text.len(),
-            0, 0,
-            self.mood_description.len(),
-            self.mood_reason.len(),
+    fn build_custom_fields(&self) -> Option<Vec<entries::CustomValue>> {
+        let values: Vec<entries::CustomValue> = self.custom_fields.iter().zip(&self.custom_inputs).filter_map(|(field, input)| {
+            Some(entries::CustomValue { name: field.name.clone(), value: input.get_value()? })
+        }).chain(self.kept_custom_values.iter().cloned()).collect();
+        if values.is_empty() { None } else { Some(values) }
+    }
+    
+    // the line (within the window) of each custom field's heading; the inputs follow directly below
+    fn get_custom_field_rows(&self) -> Vec<usize> {
+        // starting below the mood reason
+        let mut row = EVENTS_LINE + 13 + self.events.Len() + self.food.Len() + 3 * self.mood_dimensions.len();
+        let mut rows = vec![];
+        for input in &self.custom_inputs {
+            rows.push(row);
+            row += input.get_height() + 2;  // the heading and the gap after
+        }
+        rows
+    }
+    
+    // the line (within the window) each field's input starts on, with its heading on the line above
+    // the date picker and create button sit side by side at the bottom, so they're placed separately
+    fn get_field_lines(&self) -> Vec<(CreationField, usize)> {
+        let foods_line = EVENTS_LINE + 2 + self.events.Len();
+        let mood_line = foods_line + 3 + self.food.Len();
+        // below the mood's slider and the slider (with its heading) for each dimension
+        let description_line = mood_line + 3 + 3 * self.mood_dimensions.len();
+        let mut lines = vec![
+            (CreationField::Title, 2),
+            (CreationField::Text, 5),
+            (CreationField::Events, EVENTS_LINE + 1),
+            (CreationField::Foods, foods_line + 1),
+            (CreationField::Mood, mood_line),
         ];
+        lines.extend((0..self.mood_dimensions.len()).map(|index| (CreationField::Dimension(index), mood_line + 3 * (index + 1))));
+        lines.push((CreationField::MoodDescription, description_line));
+        lines.push((CreationField::MoodReason, description_line + 3));
+        lines.extend(self.get_custom_field_rows().into_iter().enumerate().map(|(index, row)| (CreationField::Custom(index), row + 1)));
+        lines
     }
     
-    pub fn handle_events(&mut self, key_parser: &KeyParser) {
+    // the text above a field's input
+    fn get_field_heading(&self, field: CreationField) -> String {
+        match field {
+            CreationField::Title => tr("title").to_string(),
+            CreationField::Text => tr("entry_text").to_string(),
+            CreationField::Events => tr("add_events").to_string(),
+            CreationField::Foods => tr("add_foods").to_string(),
+            CreationField::Mood => tr("mood_scale").to_string(),
+            CreationField::Dimension(index) => {
+                let dimension = &self.mood_dimensions[index];
+                match dimension.get_label(self.dimension_values[index].GetValue()) {
+                    Some(label) => format!("{} ({}-{}): {}", dimension.name, dimension.min, dimension.max, label),
+                    None => format!("{} ({}-{})", dimension.name, dimension.min, dimension.max),
+                }
+            },
+            CreationField::MoodDescription => tr("mood_description").to_string(),
+            CreationField::MoodReason => tr("mood_reason").to_string(),
+            CreationField::Custom(index) => match &self.custom_inputs[index] {
+                CustomInput::List(_) => locale::get().format("add_item", &[("name", &self.custom_fields[index].name)]),
+                _ => self.custom_fields[index].name.clone(),
+            },
+            CreationField::Date => tr("date_time").to_string(),
+            CreationField::Create => tr("create").to_string(),
+        }
+    }
+    
+    // the widget editing a field, if it has one
+    fn get_widget(&self, field: CreationField) -> Option<&dyn Widget> {
+        match field {
+            CreationField::Title => Some(&self.title),
+            CreationField::Text => Some(&self.text),
+            CreationField::Events => Some(&self.events),
+            CreationField::Foods => Some(&self.food),
+            CreationField::Mood => Some(&self.mood_quality),
+            CreationField::Dimension(index) => Some(&self.dimension_values[index]),
+            CreationField::MoodDescription => Some(&self.mood_description),
+            CreationField::MoodReason => Some(&self.mood_reason),
+            CreationField::Custom(index) => self.custom_inputs[index].get_widget(),
+            CreationField::Create => Some(&self.create_button),
+            CreationField::Date => None,
+        }
+    }
+    
+    fn get_widget_mut(&mut self, field: CreationField) -> Option<&mut dyn Widget> {
+        match field {
+            CreationField::Title => Some(&mut self.title),
+            CreationField::Text => Some(&mut self.text),
+            CreationField::Events => Some(&mut self.events),
+            CreationField::Foods => Some(&mut self.food),
+            CreationField::Mood => Some(&mut self.mood_quality),
+            CreationField::Dimension(index) => Some(&mut self.dimension_values[index]),
+            CreationField::MoodDescription => Some(&mut self.mood_description),
+            CreationField::MoodReason => Some(&mut self.mood_reason),
+            CreationField::Custom(index) => self.custom_inputs[index].get_widget_mut(),
+            CreationField::Create => Some(&mut self.create_button),
+            CreationField::Date => None,
+        }
+    }
+    
+    // the width of the entry text box
+    fn get_text_width(area: &TermRender::Rect) -> usize {
+        (ScreenLayout::new(area).popup_text().size.0 as usize).saturating_sub(4).clamp(1, TEXT_AREA_MAX_WIDTH)
+    }
+    
+    // fits the entry text box to the terminal
+    pub fn resize(&mut self, area: &TermRender::Rect) {
+        self.text.Resize((Self::get_text_width(area), TEXT_AREA_HEIGHT));
+    }
+    
+    pub fn handle_events(&mut self, key_parser: &KeyParser, area: &TermRender::Rect, logs: &mut Logs, index: Option<usize>) {
+        self.resize(area);
         if key_parser.ContainsKeyCode(KeyCode::Return) {
-            self.selected_field = None;
+            let focused = self.selected_field;
+            match focused {
+                // return is a new line in the entry text, and pushes the create button
+                Some(CreationField::Text) | Some(CreationField::Create) => {},
+                // a list's heading is its add button
+                Some(field) if let Some(list) = self.get_list_mut(field) && list.GetSelected().is_none() => {
+                    list.Add();
+                    return;
+                },
+                _ => self.selected_field = None,
+            }
         }
         if key_parser.ContainsKeyCode(KeyCode::Escape) {
             match &self.selected_field {
-                Some(err) => self.selected_field = None,
+                Some(_) => self.selected_field = None,
                 None => self.dead = true,
             }
         }
@@ -410,394 +1079,778 @@ impl CreatorButton {
             false => String::new()
         };
         
+        if key_parser.ContainsKeyCode(KeyCode::Tab) {
+            // Tab takes the top suggestion once something's been typed, otherwise it moves on to the next field
+            let typed = self.get_active_list().is_some_and(|(list, _, _)| list.GetSelected().is_some_and(|item| !list.GetItems()[item].is_empty()));
+            let suggestion = self.get_suggestions(area).filter(|_| typed).map(|(_, suggestions)| suggestions[0].to_string());
+            match suggestion {
+                Some(suggestion) if !key_parser.ContainsModifier(&KeyModifiers::Shift) => self.accept_suggestion(&suggestion),
+                _ => self.move_focus(key_parser.ContainsModifier(&KeyModifiers::Shift)),
+            }
+            self.release_lists();
+            return;
+        }
+        
         match self.selected_field {
-            Some(CreationField::Title) => {
-                self.title.insert_str(self.cursors[0], &typed_text);
-                self.cursors[0] += typed_text.len();
-                if key_parser.ContainsKeyCode(KeyCode::Delete) {
-                    if self.cursors[0] > self.title.len() || self.title.is_empty() || self.cursors[0] == 0 {  return;  }
-                    self.title.remove(self.cursors[0].saturating_sub(1));
-                    self.cursors[0] -= 1;
-                }
+            Some(CreationField::Date) => {
                 if key_parser.ContainsKeyCode(KeyCode::Left) {
-                    self.cursors[0] = self.cursors[0].saturating_sub(1);
+                    self.date_segment = self.date_segment.saturating_sub(1);
                 }
                 if key_parser.ContainsKeyCode(KeyCode::Right) {
-                    self.cursors[0] = usize::min(self.cursors[0] + 1, self.title.len());
+                    self.date_segment = usize::min(self.date_segment + 1, 4);
                 }
-            },
-            Some(CreationField::Text) => {
-                self.text.insert_str(self.cursors[1], &typed_text);
-                self.cursors[1] += typed_text.len();
-                if key_parser.ContainsKeyCode(KeyCode::Delete) {
-                    if self.cursors[1] > self.text.len() || self.text.is_empty() || self.cursors[1] == 0 {  return;  }
-                    self.text.remove(self.cursors[1].saturating_sub(1));
-                    self.cursors[1] -= 1;
-                }
-                if key_parser.ContainsKeyCode(KeyCode::Left) {
-                    self.cursors[1] = self.cursors[1].saturating_sub(1);
+                if key_parser.ContainsKeyCode(KeyCode::Up) {
+                    self.adjust_time(1);
                 }
-                if key_parser.ContainsKeyCode(KeyCode::Right) {
-                    self.cursors[1] = usize::min(self.cursors[1] + 1, self.text.len());
+                if key_parser.ContainsKeyCode(KeyCode::Down) {
+                    self.adjust_time(-1);
                 }
             },
-            Some(CreationField::Events) => {
-                if self.cursors[2] >= self.events.len() {  return;  }
-                let text_field = &mut self.events[self.cursors[2]];
-                text_field.push_str(&typed_text);
-                if key_parser.ContainsKeyCode(KeyCode::Delete) {
-                    text_field.pop();
-                }
+            // space toggles yes/no fields
+            Some(CreationField::Custom(index)) if let CustomInput::Boolean(value) = &mut self.custom_inputs[index] && typed_text.contains(' ') => {
+                *value = !*value;
             },
-            Some(CreationField::Foods) => {
-                if self.cursors[3] >= self.food.len() {  return;  }
-                let text_field = &mut self.food[self.cursors[3]];
-                text_field.push_str(&typed_text);
-                if key_parser.ContainsKeyCode(KeyCode::Delete) {
-                    text_field.pop();
+            Some(field) => {
+                // the create button is the only one in the menu
+                let response = self.get_widget_mut(field).map(|widget| widget.HandleKeys(key_parser, &typed_text));
+                if response == Some(widgets::Response::Pressed) {
+                    self.create(logs, index);
+                    return;
                 }
             },
-            Some(CreationField::MoodDescription) => {
-                self.mood_description.insert_str(self.cursors[4], &typed_text);
-                self.cursors[4] += typed_text.len();
-                if key_parser.ContainsKeyCode(KeyCode::Delete) {
-                    if self.cursors[4] > self.mood_description.len() || self.mood_description.is_empty() || self.cursors[4] == 0 {  return;  }
-                    self.mood_description.remove(self.cursors[4].saturating_sub(1));
-                    self.cursors[4] -= 1;
-                }
-                if key_parser.ContainsKeyCode(KeyCode::Left) {
-                    self.cursors[4] = self.cursors[4].saturating_sub(1);
-                }
-                if key_parser.ContainsKeyCode(KeyCode::Right) {
-                    self.cursors[4] = usize::min(self.cursors[4] + 1, self.mood_description.len());
-                }
+            None => {},
+        }
+        self.release_lists();
+    }
+    
+    // the list a field edits, if it's one of the lists
+    fn get_list(&self, field: CreationField) -> Option<&widgets::ListInput> {
+        match field {
+            CreationField::Events => Some(&self.events),
+            CreationField::Foods => Some(&self.food),
+            CreationField::Custom(index) => match &self.custom_inputs[index] {
+                CustomInput::List(items) => Some(items),
+                _ => None,
             },
-            Some(CreationField::MoodReason) => {
-                self.mood_reason.insert_str(self.cursors[5], &typed_text);
-                self.cursors[5] += typed_text.len();
-                if key_parser.ContainsKeyCode(KeyCode::Delete) {
-                    if self.cursors[5] > self.mood_reason.len() || self.mood_reason.is_empty() || self.cursors[5] == 0 {  return;  }
-                    self.mood_reason.remove(self.cursors[5].saturating_sub(1));
-                    self.cursors[5] -= 1;
-                }
-                if key_parser.ContainsKeyCode(KeyCode::Left) {
-                    self.cursors[5] = self.cursors[5].saturating_sub(1);
-                }
-                if key_parser.ContainsKeyCode(KeyCode::Right) {
-                    self.cursors[5] = usize::min(self.cursors[5] + 1, self.mood_reason.len());
-                }
+            _ => None,
+        }
+    }
+    
+    fn get_list_mut(&mut self, field: CreationField) -> Option<&mut widgets::ListInput> {
+        match field {
+            CreationField::Events => Some(&mut self.events),
+            CreationField::Foods => Some(&mut self.food),
+            CreationField::Custom(index) => match &mut self.custom_inputs[index] {
+                CustomInput::List(items) => Some(items),
+                _ => None,
             },
-            _ => {}
+            _ => None,
         }
     }
     
-    pub fn handle_held_mouse (&mut self, _key_parser: &KeyParser, event: &MouseEvent, area: &TermRender::Rect, _logs: &mut Logs, _index: Option<usize>) {
-        let half_width = area.width / 2;
-        let starting_index = 15 + self.events.len() + 2 + self.food.len() + 2;
-        if event.position.1 == starting_index as u16 - 1 && event.position.0 >= half_width - 10 && event.position.0 <= half_width + 10 {
-            // adjusting the mood quality
-            let quality = event.position.0 - (half_width - 10);
-            self.mood_quality = (quality / 2).clamp(1, 10) as usize;
-            return;
+    // everything Tab stops on, from the top of the menu down
+    // a list is stopped on at its heading (its add button) and then at each of its items
+    fn get_focus_order(&self) -> Vec<(CreationField, Option<usize>)> {
+        let list_stops = |field: CreationField, length: usize| {
+            std::iter::once((field, None)).chain((0..length).map(move |item| (field, Some(item))))
+        };
+        let mut order = vec![(CreationField::Title, None), (CreationField::Text, None)];
+        order.extend(list_stops(CreationField::Events, self.events.Len()));
+        order.extend(list_stops(CreationField::Foods, self.food.Len()));
+        order.push((CreationField::Mood, None));
+        order.extend((0..self.mood_dimensions.len()).map(|index| (CreationField::Dimension(index), None)));
+        order.push((CreationField::MoodDescription, None));
+        order.push((CreationField::MoodReason, None));
+        for (index, input) in self.custom_inputs.iter().enumerate() {
+            match input {
+                CustomInput::List(items) => order.extend(list_stops(CreationField::Custom(index), items.Len())),
+                _ => order.push((CreationField::Custom(index), None)),
+            }
         }
+        order.push((CreationField::Date, None));
+        order.push((CreationField::Create, None));
+        order
     }
     
-    pub fn handle_mouse_events_for_creator(&mut self, _key_parser: &KeyParser, event: &MouseEvent, area: &TermRender::Rect, logs: &mut Logs, index: Option<usize>) {
-        // checking for a text field being selected
-        let title_width = self.title.len() as u16 / 2 + 5;
-        let half_width = area.width / 2;
-        if event.position.0 >= half_width - title_width && event.position.0 <= half_width + title_width && event.position.1 == 8 {
-            self.selected_field = match &self.selected_field {
-                Some(field) if field == &CreationField::Title => None,
-                _ => Some(CreationField::Title),
-            };
-            return;
+    // moves the focus to the next (or previous) stop, wrapping around at either end
+    fn move_focus(&mut self, backwards: bool) {
+        let order = self.get_focus_order();
+        let current = self.selected_field.and_then(|field| {
+            let item = self.get_list(field).and_then(|list| list.GetSelected());
+            order.iter().position(|stop| *stop == (field, item))
+        });
+        let next = match (current, backwards) {
+            (Some(index), false) => (index + 1) % order.len(),
+            (Some(index), true) => (index + order.len() - 1) % order.len(),
+            (None, false) => 0,
+            (None, true) => order.len() - 1,
+        };
+        let (field, item) = order[next];
+        self.selected_field = Some(field);
+        if let Some(list) = self.get_list_mut(field) {
+            match item {
+                Some(item) => list.Select(item),
+                None => list.Deselect(),
+            }
         }
-        let text_width = self.text.len() as u16 / 2 + 5;
-        if event.position.0 >= half_width - text_width && event.position.0 <= half_width + text_width && event.position.1 == 11 {
-            self.selected_field = match &self.selected_field {
-                Some(field) if field == &CreationField::Text => None,
-                _ => Some(CreationField::Text),
-            };
-            return;
+    }
+    
+    // whether a field's heading should be highlighted (lists only while none of their items are being edited)
+    // text fields show their cursor instead
+    fn is_heading_focused(&self, field: CreationField) -> bool {
+        let text = matches!(field, CreationField::Title | CreationField::Text | CreationField::MoodDescription | CreationField::MoodReason);
+        !text && self.selected_field == Some(field) && self.get_list(field).is_none_or(|list| list.GetSelected().is_none())
+    }
+    
+    fn get_lists_mut(&mut self) -> Vec<&mut widgets::ListInput> {
+        let mut lists = vec![&mut self.events, &mut self.food];
+        for input in self.custom_inputs.iter_mut() {
+            if let CustomInput::List(items) = input {
+                lists.push(items);
+            }
         }
-        
-        // checking for the create button being pushed
-        //area.width as usize - 50 - 13
-        if event.position.0 >= area.width - 38 && event.position.0 < area.width - 26 &&
-           event.position.1 <= area.height - 6 && event.position.1 >= area.height - 9 {
-            // creating the thingy
-            if let Some(index) = index {
-                logs[index] = entries::Log::new(self.title.clone(), self.text.clone());
-                for event in &self.events {
-                    logs[index].add_event(event.clone());
+        lists
+    }
+    
+    // the list being edited, if it has an item focused, with its history and the line of that item
+    fn get_active_list(&self) -> Option<(&widgets::ListInput, &suggestions::History, usize)> {
+        let field = self.selected_field?;
+        let history = match field {
+            CreationField::Events => &self.event_history,
+            CreationField::Foods => &self.food_history,
+            CreationField::Custom(index) => &self.custom_histories[index],
+            _ => return None,
+        };
+        let list = self.get_list(field)?;
+        let (_, start) = self.get_field_lines().into_iter().find(|(line_field, _)| *line_field == field)?;
+        Some((list, history, start + list.GetSelected()?))
+    }
+    
+    // the suggestions for the list item being edited, and where the dropdown showing them goes on screen
+    // (its top left corner, border included), right below the item unless that would run off the menu
+    pub fn get_suggestions(&self, area: &TermRender::Rect) -> Option<((u16, u16), Vec<&str>)> {
+        let (list, history, line) = self.get_active_list()?;
+        let item = list.GetSelected()?;
+        if self.taken_suggestion.as_ref() == Some(&list.GetItems()[item]) {  return None;  }
+        let suggestions = history.get_suggestions(&list.GetItems()[item], list.GetItems(), MAX_SUGGESTIONS);
+        if suggestions.is_empty() {  return None;  }
+        // the menu's text starts inside its border
+        let menu = ScreenLayout::new(area).popup_text();
+        let column = menu.position.0 as usize + list.GetItemStart(item, menu.size.0 as usize);
+        let row = menu.position.1 as usize + line;
+        let height = suggestions.len() + 2;
+        let top = match row + 1 + height > (menu.position.1 + menu.size.1) as usize {
+            true => row.saturating_sub(height),
+            false => row + 1,
+        };
+        Some(((column as u16 - 1, top as u16), suggestions))
+    }
+    
+    // the lines of the dropdown, with the one Tab would take highlighted
+    pub fn get_suggestion_text(suggestions: &[&str]) -> Vec<TermRender::Span> {
+        let theme = Theme::Current();
+        let width = suggestions.iter().map(|suggestion| TermRender::GetDisplayWidth(suggestion)).max().unwrap_or(0);
+        suggestions.iter().enumerate().map(|(index, suggestion)| {
+            let text = format!(" {}{} ", suggestion, " ".repeat(width - TermRender::GetDisplayWidth(suggestion)));
+            TermRender::Span::FromTokens(vec![text.Stylize(match index {
+                0 => &theme.highlight,
+                _ => &theme.text,
+            })])
+        }).collect()
+    }
+    
+    // the size of the dropdown, border included
+    pub fn get_suggestion_size(lines: &[TermRender::Span]) -> (u16, u16) {
+        let width = lines.iter().map(|line| line.Size()).max().unwrap_or(0);
+        (width as u16 + 2, lines.len() as u16 + 2)
+    }
+    
+    // takes the suggestion on a line of the dropdown
+    pub fn click_suggestion(&mut self, line: usize, area: &TermRender::Rect) {
+        let Some((_, suggestions)) = self.get_suggestions(area) else {  return;  };
+        if let Some(suggestion) = suggestions.get(line).map(|suggestion| suggestion.to_string()) {
+            self.accept_suggestion(&suggestion);
+        }
+    }
+    
+    fn accept_suggestion(&mut self, suggestion: &str) {
+        if let Some(field) = self.selected_field && let Some(list) = self.get_list_mut(field) {
+            list.ReplaceSelected(suggestion);
+            self.taken_suggestion = Some(suggestion.to_string());
+        }
+    }
+    
+    // lists that lost focus drop their empty items
+    fn release_lists(&mut self) {
+        if self.selected_field != Some(CreationField::Events) {
+            self.events.Deselect();
+        }
+        if self.selected_field != Some(CreationField::Foods) {
+            self.food.Deselect();
+        }
+        for (index, input) in self.custom_inputs.iter_mut().enumerate() {
+            if let CustomInput::List(items) = input && self.selected_field != Some(CreationField::Custom(index)) {
+                items.Deselect();
+            }
+        }
+    }
+    
+    // moves the selected part of the date picker up or down by one
+    fn adjust_time(&mut self, step: i32) {
+        let months = |count: i32| match step > 0 {
+            true => self.entry_time.checked_add_months(chrono::Months::new(count as u32)),
+            false => self.entry_time.checked_sub_months(chrono::Months::new(count as u32)),
+        };
+        // months that are too short for the day (the 31st) are clamped to their last day
+        let adjusted = match self.date_segment {
+            0 => months(12),
+            1 => months(1),
+            2 => self.entry_time.checked_add_signed(chrono::TimeDelta::days(step as i64)),
+            3 => self.entry_time.checked_add_signed(chrono::TimeDelta::hours(step as i64)),
+            _ => self.entry_time.checked_add_signed(chrono::TimeDelta::minutes(step as i64)),
+        };
+        if let Some(time) = adjusted {
+            self.entry_time = time;
+            self.kept_date = None;
+        }
+    }
+    
+    fn get_entry_time(&self) -> chrono::DateTime<chrono::Local> {
+        use chrono::TimeZone;
+        // a time skipped by daylight savings doesn't exist locally, so it's taken as utc instead of being lost
+        chrono::Local.from_local_datetime(&self.entry_time).earliest()
+            .unwrap_or_else(|| chrono::Local.from_utc_datetime(&self.entry_time))
+    }
+    
+    // saves the entry (replacing the one being edited, if any) and closes the menu
+    fn create(&mut self, logs: &mut Logs, index: Option<usize>) {
+        let mut log = entries::Log::new(self.title.GetText().to_string(), self.text.GetText().to_string());
+        match &self.kept_date {
+            // saving an edit doesn't make up a time the entry never had
+            Some(date) => {
+                log.entry_date = date.clone();
+                log.entry_time = None;
+            },
+            None => log.set_time(self.get_entry_time()),
+        }
+        for event in self.events.GetItems().iter().filter(|event| !event.is_empty()) {
+            log.add_event(event.clone());
+        }
+        for food in self.food.GetItems().iter().filter(|food| !food.is_empty()) {
+            log.add_food(food.clone());
+        }
+        log.mood = Some(self.build_mood());
+        log.custom_fields = self.build_custom_fields();
+        // an edited entry is taken out and put back, since its date may have changed
+        if let Some(index) = index {
+            logs.remove(index);
+        }
+        self.saved_index = Some(logs.insert_sorted(log));
+        self.dead = true;
+    }
+    
+    fn build_mood(&self) -> entries::Mood {
+        let dimensions = self.mood_dimensions.iter().zip(&self.dimension_values).map(|(dimension, value)| {
+            entries::MoodRating { name: dimension.name.clone(), value: value.GetValue() }
+        }).chain(self.kept_dimensions.iter().cloned()).collect::<Vec<_>>();
+        let reason = self.mood_reason.GetText();
+        entries::Mood {
+            quality: self.mood_quality.GetValue() as usize,
+            description: self.mood_description.GetText().to_string(),
+            reason: if reason.is_empty() { None } else { Some(reason.to_string()) },
+            dimensions: if dimensions.is_empty() { None } else { Some(dimensions) },
+        }
+    }
+    
+    pub fn handle_mouse_events(&mut self, hit: &TermRender::MouseHit, action: TermRender::MouseAction, area: &TermRender::Rect, logs: &mut Logs, index: Option<usize>) {
+        match action {
+            TermRender::MouseAction::Click => {
+                // letting go of a dragged item isn't a click on wherever it was dropped
+                let mut dragged = false;
+                for list in self.get_lists_mut() {
+                    dragged |= list.EndDrag();
                 }
-                for food in &self.food {
-                    logs[index].add_food(food.clone());
+                if !dragged {
+                    self.handle_click(hit, area, logs, index);
                 }
-                logs[index].mood = Some(entries::Mood {
-                    quality: self.mood_quality,
-                    description: self.mood_description.clone(),
-                    reason: if self.mood_reason.is_empty() { None } else { Some(self.mood_reason.clone()) },
-                });
-                self.dead = true;
-                return;
-            }
-            let index = logs.len();
-            logs.push(entries::Log::new(self.title.clone(), self.text.clone()));
-            for event in &self.events {
-                logs[index].add_event(event.clone());
-            }
-            for food in &self.food {
-                logs[index].add_food(food.clone());
-            }
-            logs[index].mood = Some(entries::Mood {
-                quality: self.mood_quality,
-                description: self.mood_description.clone(),
-                reason: if self.mood_reason.is_empty() { None } else { Some(self.mood_reason.clone()) },
-            });
-            self.dead = true;
-            return;
+            },
+            // pressing on a list item picks it up, and the sliders follow the mouse while it's held
+            _ => {
+                self.handle_widget_mouse(hit, action, area);
+            },
         }
+        self.release_lists();
+    }
+    
+    // whether a column is on a centered field of the given width (with a little leeway either side)
+    fn is_on_field(form: &FormLayout, column: usize, width: usize) -> bool {
+        column.abs_diff(form.width / 2) <= width / 2 + 5
+    }
+    
+    fn handle_click(&mut self, hit: &TermRender::MouseHit, area: &TermRender::Rect, logs: &mut Logs, index: Option<usize>) {
+        let (line, column) = (hit.line, hit.column);
         
-        if event.position.0 >= half_width - 10 && event.position.0 <= half_width + 10 && event.position.1 == 13 {
-            self.selected_field = Some(CreationField::Events);
-            self.cursors[2] = self.events.len();
-            self.events.push(String::new());
+        // checking for the create button being pushed (the last three lines, on the right)
+        let form = self.get_form_layout(area);
+        let last_line = form.height.saturating_sub(1);
+        let button_width = self.create_button.GetWidth();
+        if line + 2 >= last_line && column >= form.button_column &&
+            self.create_button.HandleMouse(line + 2 - last_line, column - form.button_column, TermRender::MouseAction::Click, button_width) == widgets::Response::Pressed {
+            self.create(logs, index);
             return;
         }
         
-        // checking for individual event elements
-        if event.position.1 > 13 && event.position.1 <= 13 + self.events.len() as u16 &&
-           event.position.0 >= half_width - 5 - self.events[event.position.1 as usize - 14].len() as u16 / 2 &&
-           event.position.0 <= half_width + 5 + self.events[event.position.1 as usize - 14].len() as u16 / 2
-        {
-            self.selected_field = Some(CreationField::Events);
-            self.cursors[2] = event.position.1 as usize - 14;
-            
+        // checking for the date picker (to the left of the create button)
+        if line + 2 == last_line && column < DATE_PICKER_SPANS[6].end {
+            self.selected_field = Some(CreationField::Date);
             return;
         }
-        
-        // checking for foods
-        let starting_index = 15 + self.events.len();
-        if event.position.0 >= half_width - 10 && event.position.0 <= half_width + 10 && event.position.1 == starting_index as u16 {
-            self.selected_field = Some(CreationField::Foods);
-            self.cursors[3] = self.food.len();
-            self.food.push(String::new());
+        if line + 1 == last_line && let Some(part) = DATE_PICKER_SPANS.iter().position(|span| span.contains(&column)) {
+            match part {
+                0 => self.adjust_time(-1),
+                6 => self.adjust_time(1),
+                segment => self.date_segment = segment - 1,
+            }
+            self.selected_field = Some(CreationField::Date);
             return;
         }
         
-        // checking for individual event elements
-        if event.position.1 > starting_index as u16 && event.position.1 <= starting_index as u16 + self.food.len() as u16 &&
-            event.position.0 >= half_width - 5 - self.food[event.position.1 as usize - starting_index - 1].len() as u16 / 2 &&
-            event.position.0 <= half_width + 5 + self.food[event.position.1 as usize - starting_index - 1].len() as u16 / 2
-        {
-            self.selected_field = Some(CreationField::Foods);
-            self.cursors[3] = event.position.1 as usize - starting_index - 1;
-            return;
+        for (field, start) in self.get_field_lines() {
+            // the headings of the lists double as add buttons
+            let heading_width = TermRender::GetDisplayWidth(&self.get_field_heading(field)) + 2;
+            if line + 1 == start && Self::is_on_field(&form, column, heading_width) && let Some(list) = self.get_list_mut(field) {
+                list.Add();
+                self.selected_field = Some(field);
+                return;
+            }
+            // yes/no fields are toggled by clicking anywhere along them
+            if line == start && let CreationField::Custom(index) = field && let CustomInput::Boolean(value) = &mut self.custom_inputs[index] {
+                *value = !*value;
+                self.selected_field = Some(field);
+                return;
+            }
         }
         
-        let starting_index = 15 + self.events.len() + 2 + self.food.len() + 2;
-        
-        if event.position.1 == starting_index as u16 + 2 && event.position.0 >= half_width - self.mood_description.len() as u16 - 5 &&
-           event.position.0 <= half_width + self.mood_description.len() as u16 + 5
-        {
-            self.selected_field = Some(CreationField::MoodDescription);
-            return;
+        if !self.handle_widget_mouse(hit, TermRender::MouseAction::Click, area) {
+            self.selected_field = None;  // clicked outside a field
         }
-        
-        if event.position.1 == starting_index as u16 + 5 && event.position.0 >= half_width - self.mood_reason.len() as u16 - 5 &&
-           event.position.0 <= half_width + self.mood_reason.len() as u16 + 5
-        {
-            self.selected_field = Some(CreationField::MoodReason);
-            return;
+    }
+    
+    // passes the mouse on to the field under it, focusing the field if it was used
+    fn handle_widget_mouse(&mut self, hit: &TermRender::MouseHit, action: TermRender::MouseAction, area: &TermRender::Rect) -> bool {
+        let width = self.get_form_layout(area).width;
+        for (field, start) in self.get_field_lines() {
+            if hit.line < start {  continue;  }
+            let Some(widget) = self.get_widget_mut(field) else {  continue;  };
+            if hit.line >= start + widget.Height() {  continue;  }
+            if widget.HandleMouse(hit.line - start, hit.column, action, width).IsUsed() {
+                self.selected_field = Some(field);
+                return true;
+            }
         }
-        
-        self.selected_field = None;  // clicked outside a field
+        false
     }
     
     pub fn get_window_text(&self, area: &TermRender::Rect) -> Vec<TermRender::Span> {
         // a bunch of blank elements to make it easier (in other words, I'm lazy)
         
+        let form = self.get_form_layout(area);
         let mut render = vec![];
-        for _ in 0..area.height - 12 {
+        for _ in 0..form.height {
             render.push(TermRender::Span::FromTokens(vec!["".Colorizes(vec![])]));
         }
         
-        // adding the field for title
-        render[1] = TermRender::Span::FromTokens(vec![
-            Self::center_padding(area, "*Title*".len()),
-            "*Title*".Colorizes(vec![TermRender::ColorType::Italic, TermRender::ColorType::BrightWhite]),
-        ]);
-        let field_text = String::from(match self.title.is_empty() {
-            true => "-- Title Here --",
-            false => &self.title
-        });
-        render[2] = TermRender::Span::FromTokens(vec![
-            Self::center_padding(area, field_text.len()),
-            field_text.Colorizes({
-                match self.selected_field {
-                    Some(CreationField::Title) => vec![TermRender::ColorType::White, TermRender::ColorType::Underline],
-                    _ => vec![TermRender::ColorType::White],
-                }
-            }),
-        ]);
-        
-        // adding the field for text
-        render[4] = TermRender::Span::FromTokens(vec![
-            Self::center_padding(area, "*Entry Text*".len()),
-            "*Entry Text*".Colorizes(vec![TermRender::ColorType::Italic, TermRender::ColorType::BrightWhite]),
-        ]);
-        let field_text = String::from(match self.text.is_empty() {
-            true => "-- Text Here --",
-            false => &self.text
-        });
-        render[5] = TermRender::Span::FromTokens(vec![
-            Self::center_padding(area, field_text.len()),
-            field_text.Colorizes({
-                match self.selected_field {
-                    Some(CreationField::Text) => vec![TermRender::ColorType::White, TermRender::ColorType::Underline],
-                    _ => vec![TermRender::ColorType::White],
-                }
-            }),
-        ]);
-        
-        // rendering the button to add another event
-        render[7] = TermRender::Span::FromTokens(vec![
-            Self::center_padding(area, "*Add Event*".len()),
-            "*Add Events*".Colorizes(vec![TermRender::ColorType::BrightWhite, TermRender::ColorType::Italic])
-        ]);
-        
-        // rendering the current events
-        let mut index = 8;
-        for event in &self.events {
-            let field_text = String::from(match event.is_empty() {
-                true => "-- Text Here --",
-                false => event
-            });
-            render[index] = TermRender::Span::FromTokens(vec![
-                Self::center_padding(area, field_text.len()),
-                field_text.Colorizes({
-                    if self.selected_field == Some(CreationField::Events) && self.cursors[2] == index - 8 {
-                        vec![TermRender::ColorType::White, TermRender::ColorType::Underline]
-                    } else {  vec![TermRender::ColorType::White]  }
-                })
-            ]);
-            index += 1;
-        }
-        
-        // rendering the button to add another event
-        index += 1;
-        render[index] = TermRender::Span::FromTokens(vec![
-            Self::center_padding(area, "*Add Foods*".len()),
-            "*Add Foods*".Colorizes(vec![TermRender::ColorType::BrightWhite, TermRender::ColorType::Italic])
-        ]);
-        index += 1;
-        
-        // rendering the current events
-        let start_index = index;
-        for item in &self.food {
-            let field_text = String::from(match item.is_empty() {
-                true => "-- Text Here --",
-                false => item
-            });
-            render[index] = TermRender::Span::FromTokens(vec![
-                Self::center_padding(area, field_text.len()),
-                field_text.Colorizes({
-                    if self.selected_field == Some(CreationField::Foods) && self.cursors[3] == index - start_index {
-                        vec![TermRender::ColorType::White, TermRender::ColorType::Underline]
-                    } else {  vec![TermRender::ColorType::White]  }
-                })
-            ]);
-            index += 1;
-        }
-        
-        index += 1;
-        render[index] = TermRender::Span::FromTokens(vec![
-            Self::center_padding(area, "*Mood (1-10)*".len()),
-            "*Mood (1-10)*".Colorizes(vec![TermRender::ColorType::BrightWhite, TermRender::ColorType::Italic])
-        ]);
-        index += 1;
-        
-        // rendering mood quality (slider of sorts ig)
-        // slides from left to right, using a white background with bright white slider, and black text
-        let quality_text = vec![
-            "==".repeat(self.mood_quality - 1).Colorizes(vec![TermRender::ColorType::OnWhite, TermRender::ColorType::BrightBlack]),
-            format!("{:=>2}", self.mood_quality).Colorizes(vec![TermRender::ColorType::Black, TermRender::ColorType::OnBrightWhite]),
-            "==".repeat(10 - self.mood_quality).Colorizes(vec![TermRender::ColorType::OnWhite, TermRender::ColorType::BrightBlack]),
-        ];  // the total size is 2 * 10 or 20
-        render[index] = TermRender::Span::FromTokens(vec![
-            Self::center_padding(area, 20),
-            quality_text[0].clone(),
-            quality_text[1].clone(),
-            quality_text[2].clone(),
-        ]);
-        index += 2;
-        
-        render[index] = TermRender::Span::FromTokens(vec![
-            Self::center_padding(area, "*Mood Description*".len()),
-            "*Mood Description*".Colorizes(vec![TermRender::ColorType::BrightWhite, TermRender::ColorType::Italic])
-        ]);
-        index += 1;
-        
-        // rendering mood description
-        let field_text = String::from(match self.mood_description.is_empty() {
-            true => "-- Text Here --",
-            false => &self.mood_description
-        });
-        render[index] = TermRender::Span::FromTokens(vec![
-            Self::center_padding(area, field_text.len()),
-            field_text.Colorizes(match self.selected_field {
-                Some(CreationField::MoodDescription) => vec![TermRender::ColorType::White, TermRender::ColorType::Underline],
-                _ => vec![TermRender::ColorType::White],
-            })
-        ]);
-        index += 2;
-        
-        render[index] = TermRender::Span::FromTokens(vec![
-            Self::center_padding(area, "*Mood Reason*".len()),
-            "*Mood Reason*".Colorizes(vec![TermRender::ColorType::BrightWhite, TermRender::ColorType::Italic])
-        ]);
-        index += 1;
-        
-        // rendering mood reason
-        let field_text = String::from(match self.mood_reason.is_empty() {
-            true => "-- Text Here --",
-            false => &self.mood_reason
-        });
-        render[index] = TermRender::Span::FromTokens(vec![
-            Self::center_padding(area, field_text.len()),
-            field_text.Colorizes(match self.selected_field {
-                Some(CreationField::MoodReason) => vec![TermRender::ColorType::White, TermRender::ColorType::Underline],
-                _ => vec![TermRender::ColorType::White],
-            })
-        ]);
-        index += 2;
-        
-        // adding the button for completion
+        // every field, with its heading above (stopping before the create button if there are too many to fit)
+        let width = form.width;
+        let last_line = render.len().saturating_sub(3);
+        for (field, start) in self.get_field_lines() {
+            let mut lines = vec![Self::get_heading(width, &self.get_field_heading(field), self.is_heading_focused(field))];
+            lines.append(&mut self.get_field_text(field, width));
+            for (offset, line) in lines.into_iter().enumerate() {
+                if start - 1 + offset >= last_line {  break;  }
+                render[start - 1 + offset] = line;
+            }
+        }
+        
+        // adding the date picker and the button for completion, side by side (if there's room for them)
+        if render.len() < 3 {  return render;  }
         let render_len = render.len() - 1;
-        let padding = " ".repeat(area.width as usize - 50 - 13).Colorizes(vec![]);
-        render[render_len - 2] = TermRender::Span::FromTokens(vec![
-            padding.clone(), "┌────────┐".Colorizes(vec![])
-        ]);
-        render[render_len - 1] = TermRender::Span::FromTokens(vec![
-            padding.clone(), "│ Create │".Colorizes(vec![])
-        ]);
-        render[render_len    ] = TermRender::Span::FromTokens(vec![
-            padding,         "└────────┘".Colorizes(vec![])
-        ]);
+        let button_column = form.button_column;
+        let button_width = self.create_button.GetWidth();
+        let button_lines = self.create_button.Render(button_width, self.selected_field == Some(CreationField::Create));
+        for ((line, mut tokens), button_line) in [render_len - 2, render_len - 1, render_len].into_iter().zip(self.get_date_picker_text()).zip(button_lines) {
+            let width = tokens.iter().map(|token| token.GetSize()).sum::<usize>();
+            tokens.push(" ".repeat(button_column.saturating_sub(width)).Colorizes(vec![]));
+            let mut span = TermRender::Span::FromTokens(tokens);
+            span.Extend(button_line);
+            render[line] = span;
+        }
         
         render
     }
     
-    fn center_padding(area: &TermRender::Rect, text_size: usize) -> TermRender::Colored {
-        let text_size = text_size / 2;
-        let center = (area.width - 50) as usize / 2;
-        let offset = center - text_size;
+    // the lines of a field's input, centered within the width
+    fn get_field_text(&self, field: CreationField, width: usize) -> Vec<TermRender::Span> {
+        let theme = Theme::Current();
+        let focused = self.selected_field == Some(field);
+        if let Some(widget) = self.get_widget(field) {
+            return widget.Render(width, focused);
+        }
+        let CreationField::Custom(index) = field else {  return vec![];  };
+        let CustomInput::Boolean(value) = &self.custom_inputs[index] else {  return vec![];  };
+        let field_text = match value {
+            true => format!("[x] {}", tr("yes")),
+            false => format!("[ ] {} ", tr("no")),
+        };
+        vec![TermRender::Span::FromTokens(vec![
+            Self::center_padding(width, TermRender::GetDisplayWidth(&field_text)),
+            field_text.Stylize(match focused {
+                true => &theme.active,
+                false => &theme.text,
+            }),
+        ])]
+    }
+    
+    // the heading, the picker itself (see DATE_PICKER_SPANS) and the date written out
+    fn get_date_picker_text(&self) -> [Vec<TermRender::Colored>; 3] {
+        use chrono::{Datelike, Timelike};
+        let selected = self.selected_field == Some(CreationField::Date);
+        let theme = Theme::Current();
+        let segments = [
+            format!("{:04}", self.entry_time.year()),
+            format!("{:02}", self.entry_time.month()),
+            format!("{:02}", self.entry_time.day()),
+            format!("{:02}", self.entry_time.hour()),
+            format!("{:02}", self.entry_time.minute()),
+        ];
+        let mut picker = vec!["  ".Colorizes(vec![]), "[-]".Stylize(&theme.strong), " ".Colorizes(vec![])];
+        for (index, (segment, separator)) in segments.iter().zip(["-", "-", " ", ":", " "]).enumerate() {
+            picker.push(segment.Stylize(match selected && self.date_segment == index {
+                true => &theme.active,
+                false => &theme.text,
+            }));
+            picker.push(separator.Stylize(&theme.text));
+        }
+        picker.push("[+]".Stylize(&theme.strong));
+        [
+            vec![format!("  *{}*", self.get_field_heading(CreationField::Date)).Stylize(&theme.heading)],
+            picker,
+            vec![format!("  {}", locale::get().format_date(&self.entry_time)).Stylize(&theme.muted)],
+        ]
+    }
+    
+    // a heading (between asterisks) centered on its line, shown inverted while it has the focus
+    fn get_heading(width: usize, heading: &str, focused: bool) -> TermRender::Span {
+        let theme = Theme::Current();
+        let heading = format!("*{}*", heading);
+        TermRender::Span::FromTokens(vec![
+            Self::center_padding(width, TermRender::GetDisplayWidth(&heading)),
+            heading.Colorizes(match focused {
+                true => theme.focused_heading.clone(),
+                false => theme.heading.clone(),
+            }),
+        ])
+    }
+    
+    fn get_form_layout(&self, area: &TermRender::Rect) -> FormLayout {
+        let text = ScreenLayout::new(area).popup_text();
+        let width = text.size.0 as usize;
+        FormLayout {
+            width,
+            height: text.size.1 as usize,
+            button_column: width.saturating_sub(self.create_button.GetWidth() + 1),
+        }
+    }
+    
+    fn center_padding(width: usize, text_size: usize) -> TermRender::Colored {
+        let offset = (width / 2).saturating_sub(text_size / 2);
         " ".repeat(offset).Colorizes(vec![])
     }
 }
 
+// the column widths in the habit tracker's grid
+const HABIT_NAME_WIDTH: usize = 18;
+const HABIT_CELL_WIDTH: usize = 5;
+const HABIT_STATS_WIDTH: usize = 28;
+// the grid starts this many lines into the window (below the day headers)
+const HABIT_GRID_START: usize = 4;
+
+// the per day check off grid for the habits defined in the config
+// the newest day is always the rightmost column
+struct HabitTracker {
+    // (habit, column) of the highlighted cell
+    cursor: (usize, usize),
+    pub dead: bool,
+}
+
+impl HabitTracker {
+    pub fn new(area: &TermRender::Rect) -> Self {
+        HabitTracker {
+            cursor: (0, Self::get_visible_days(area) - 1),  // starting on today
+            dead: false,
+        }
+    }
+    
+    fn get_visible_days(area: &TermRender::Rect) -> usize {
+        let space = (ScreenLayout::new(area).popup_text().size.0 as usize).saturating_sub(HABIT_NAME_WIDTH + HABIT_STATS_WIDTH);
+        (space / HABIT_CELL_WIDTH).clamp(1, 14)
+    }
+    
+    fn get_day(area: &TermRender::Rect, column: usize) -> chrono::NaiveDate {
+        let today = chrono::Local::now().date_naive();
+        let days_back = Self::get_visible_days(area).saturating_sub(column + 1);
+        today - chrono::Days::new(days_back as u64)
+    }
+    
+    // returns true if any completions were changed
+    pub fn handle_events(&mut self, key_parser: &KeyParser, area: &TermRender::Rect, habits: &[config::HabitDefinition], log: &mut HabitLog) -> bool {
+        if key_parser.ContainsKeyCode(KeyCode::Escape) {
+            self.dead = true;
+            return false;
+        }
+        let days = Self::get_visible_days(area);
+        self.cursor.1 = self.cursor.1.min(days - 1);  // incase the terminal was shrunk
+        if key_parser.ContainsKeyCode(KeyCode::Up) {
+            self.cursor.0 = self.cursor.0.saturating_sub(1);
+        }
+        if key_parser.ContainsKeyCode(KeyCode::Down) {
+            self.cursor.0 = usize::min(self.cursor.0 + 1, habits.len().saturating_sub(1));
+        }
+        if key_parser.ContainsKeyCode(KeyCode::Left) {
+            self.cursor.1 = self.cursor.1.saturating_sub(1);
+        }
+        if key_parser.ContainsKeyCode(KeyCode::Right) {
+            self.cursor.1 = usize::min(self.cursor.1 + 1, days - 1);
+        }
+        
+        let Some(habit) = habits.get(self.cursor.0) else {  return false;  };
+        let day = Self::get_day(area, self.cursor.1);
+        if key_parser.ContainsKeyCode(KeyCode::Return) || key_parser.ContainsChar(' ') {
+            log.check_off(day, habit);
+            return true;
+        }
+        if key_parser.ContainsKeyCode(KeyCode::Delete) {
+            log.undo(day, habit);
+            return true;
+        }
+        false
+    }
+    
+    // the (habit, column) of the grid cell under the mouse, if it's over one
+    fn get_cell_at(hit: &TermRender::MouseHit, area: &TermRender::Rect, habits: &[config::HabitDefinition]) -> Option<(usize, usize)> {
+        if hit.onBorder || hit.line < HABIT_GRID_START || hit.column < HABIT_NAME_WIDTH {  return None;  }
+        let habit_index = hit.line - HABIT_GRID_START;
+        let day_index = (hit.column - HABIT_NAME_WIDTH) / HABIT_CELL_WIDTH;
+        if habit_index >= habits.len() || day_index >= Self::get_visible_days(area) {  return None;  }
+        Some((habit_index, day_index))
+    }
+    
+    // the highlighted cell follows the mouse
+    pub fn hover(&mut self, hit: &TermRender::MouseHit, area: &TermRender::Rect, habits: &[config::HabitDefinition]) {
+        if let Some(cell) = Self::get_cell_at(hit, area, habits) {
+            self.cursor = cell;
+        }
+    }
+    
+    // clicking a cell checks it off (or counts it up); undo takes one back off
+    pub fn handle_mouse_events(&mut self, hit: &TermRender::MouseHit, area: &TermRender::Rect, habits: &[config::HabitDefinition], log: &mut HabitLog, undo: bool) -> bool {
+        let Some((habit_index, day_index)) = Self::get_cell_at(hit, area, habits) else {  return false;  };
+        self.cursor = (habit_index, day_index);
+        let habit = &habits[habit_index];
+        let day = Self::get_day(area, day_index);
+        match undo {
+            true => log.undo(day, habit),
+            false => log.check_off(day, habit),
+        }
+        true
+    }
+    
+    pub fn get_window_text(&self, area: &TermRender::Rect, habits: &[config::HabitDefinition], log: &HabitLog) -> Vec<TermRender::Span> {
+        let theme = Theme::Current();
+        let mut render = vec![TermRender::Span::FromTokens(vec![])];
+        if habits.is_empty() {
+            render.push(TermRender::Span::FromTokens(vec![
+                format!(" {}", tr("no_habits")).Stylize(&theme.label)
+            ]));
+            return render;
+        }
+        
+        // the day headers
+        let today = chrono::Local::now().date_naive();
+        let days = Self::get_visible_days(area);
+        let mut week_days = vec![" ".repeat(HABIT_NAME_WIDTH).Colorizes(vec![])];
+        let mut dates = vec![" ".repeat(HABIT_NAME_WIDTH).Colorizes(vec![])];
+        for column in 0..days {
+            let day = Self::get_day(area, column);
+            let colors = match day == today {
+                true => &theme.strong,
+                false => &theme.text,
+            };
+            week_days.push(format!("{:^width$}", locale::get().get_short_week_day(&day), width = HABIT_CELL_WIDTH).Stylize(colors));
+            dates.push(format!("{:^width$}", day.format("%d").to_string(), width = HABIT_CELL_WIDTH).Stylize(colors));
+        }
+        week_days.push(format!("  {:>4}  {:>6}  {:>4}", tr("habit_rate"), tr("habit_streak"), tr("habit_best")).Stylize(&theme.label));
+        render.push(TermRender::Span::FromTokens(week_days));
+        render.push(TermRender::Span::FromTokens(dates));
+        render.push(TermRender::Span::FromTokens(vec![]));
+        
+        for (habit_index, habit) in habits.iter().enumerate() {
+            let mut tokens = vec![
+                format!(" {} ", TermRender::FitToWidth(&habit.name, HABIT_NAME_WIDTH - 2)).Stylize(&theme.strong)
+            ];
+            for column in 0..days {
+                let day = Self::get_day(area, column);
+                let count = log.get(day, &habit.name);
+                let cell = match habit.kind {
+                    config::HabitKind::Check => String::from(if count > 0 { "[x]" } else { "[ ]" }),
+                    config::HabitKind::Count => format!("{}/{}", count, habit.target),
+                };
+                let mut colors = match habit.is_complete(count) {
+                    true => theme.positive.clone(),
+                    false => theme.muted.clone(),
+                };
+                if self.cursor == (habit_index, column) {
+                    colors.extend_from_slice(&theme.selection);
+                }
+                tokens.push(format!("{:^width$}", cell, width = HABIT_CELL_WIDTH).Colorizes(colors));
+            }
+            let stats = log.get_stats(habit, today);
+            tokens.push(format!(
+                "  {:>3.0}%  {:>6}  {:>4}", stats.completion_rate * 100., stats.current_streak, stats.best_streak
+            ).Stylize(&theme.text));
+            render.push(TermRender::Span::FromTokens(tokens));
+        }
+        
+        render.push(TermRender::Span::FromTokens(vec![]));
+        render.push(TermRender::Span::FromTokens(vec![
+            format!(" {}", tr("habit_hint")).Stylize(&theme.muted)
+        ]));
+        render
+    }
+}
+
+// how many days an item needs with and without it before it's shown in the analysis
+const DEFAULT_MIN_SAMPLES: usize = 3;
+// shows how the mood differs on days with and without each food/event
+struct AnalysisView {
+    // compares items against the following day's mood instead of the same day's
+    next_day: bool,
+    min_samples: usize,
+    pub dead: bool,
+}
+
+impl AnalysisView {
+    pub fn new() -> Self {
+        AnalysisView {
+            next_day: false,
+            min_samples: DEFAULT_MIN_SAMPLES,
+            dead: false,
+        }
+    }
+    
+    pub fn handle_events(&mut self, key_parser: &KeyParser) {
+        if key_parser.ContainsKeyCode(KeyCode::Escape) {
+            self.dead = true;
+        }
+        if key_parser.ContainsChar('l') {
+            self.next_day = !self.next_day;
+        }
+        if key_parser.ContainsChar('+') || key_parser.ContainsChar('=') {
+            self.min_samples += 1;
+        }
+        if key_parser.ContainsChar('-') {
+            self.min_samples = usize::max(self.min_samples - 1, 1);
+        }
+    }
+    
+    pub fn handle_mouse_events(&mut self, hit: &TermRender::MouseHit) {
+        // the options are on the second line
+        if hit.line != 1 || hit.onBorder {  return;  }
+        let column = hit.column;
+        let (lag, minus, plus) = self.get_option_spans();
+        if lag.contains(&column) {
+            self.next_day = !self.next_day;
+        } else if minus.contains(&column) {
+            self.min_samples = usize::max(self.min_samples - 1, 1);
+        } else if plus.contains(&column) {
+            self.min_samples += 1;
+        }
+    }
+    
+    fn get_lag_text(next_day: bool) -> String {
+        match next_day {
+            true => format!(" [L] {}", tr("compared_next_day")),
+            false => format!(" [L] {}", tr("compared_same_day")),
+        }
+    }
+    
+    fn get_min_days_text(min_samples: usize) -> String {
+        format!(" {} {:>2} ", tr("min_days"), min_samples)
+    }
+    
+    // the columns of the lag toggle and the -/+ buttons on the options line
+    fn get_option_spans(&self) -> (std::ops::Range<usize>, std::ops::Range<usize>, std::ops::Range<usize>) {
+        let lag = TermRender::GetDisplayWidth(&Self::get_lag_text(self.next_day));
+        let minus = lag + 4;
+        let plus = minus + 3 + TermRender::GetDisplayWidth(&Self::get_min_days_text(self.min_samples));
+        (0..lag, minus..minus + 3, plus..plus + 3)
+    }
+    
+    // every item, which the window scrolls through
+    pub fn get_window_text(&self, logs: &Logs) -> Vec<TermRender::Span> {
+        let theme = Theme::Current();
+        let mut render = vec![
+            TermRender::Span::FromTokens(vec![]),
+            TermRender::Span::FromTokens(vec![
+                Self::get_lag_text(self.next_day).Stylize(&theme.heading),
+                "    ".Colorizes(vec![]),
+                "[-]".Stylize(&theme.strong),
+                Self::get_min_days_text(self.min_samples).Stylize(&theme.heading),
+                "[+]".Stylize(&theme.strong),
+            ]),
+            TermRender::Span::FromTokens(vec![]),
+            TermRender::Span::FromTokens(vec![
+                format!(" {:<24}{:<7}{:>11}{:>13}{:>7}{:>8}", tr("item"), tr("type"), tr("with"), tr("without"), tr("difference"), tr("effect"))
+                    .Stylize(&theme.column_heading),
+            ]),
+        ];
+        
+        let correlations = analysis::analyze(logs, self.next_day, self.min_samples);
+        if correlations.is_empty() {
+            render.push(TermRender::Span::FromTokens(vec![
+                format!(" {}", tr("analysis_empty")).Stylize(&theme.label)
+            ]));
+        }
+        
+        for correlation in &correlations {
+            let kind = match correlation.kind {
+                analysis::ItemKind::Food => tr("food"),
+                analysis::ItemKind::Event => tr("event"),
+            };
+            let difference = correlation.with_mean - correlation.without_mean;
+            let colors = match difference >= 0. {
+                true => &theme.positive,
+                false => &theme.negative,
+            };
+            render.push(TermRender::Span::FromTokens(vec![
+                format!(" {}  {}", TermRender::FitToWidth(&correlation.item, 22), TermRender::FitToWidth(kind, 7)).Stylize(&theme.strong),
+                format!(
+                    "{:>11}{:>13}",
+                    format!("{:.1} ({})", correlation.with_mean, correlation.with_count),
+                    format!("{:.1} ({})", correlation.without_mean, correlation.without_count),
+                ).Stylize(&theme.text),
+                format!("{:>+7.1}{:>+8.2}", difference, correlation.effect_size).Stylize(colors),
+            ]));
+        }
+        
+        render.push(TermRender::Span::FromTokens(vec![]));
+        render.push(TermRender::Span::FromTokens(vec![
+            format!(" {}", tr("analysis_hint")).Stylize(&theme.muted)
+        ]));
+        render
+    }
+}
+
 /*
 todo!s:
 
@@ -805,25 +1858,51 @@ todo!s:
         -- now just de-jank-ify it (could be a lot smoother, but it seems to work for now at a minimum)
      
     at some point think about adding a delete option ig  (could finally get rid of the initial test file)
-        -- also delete options for the elements in lists like events
-    
-    todo! make term render correctly render borders when using emojis (which aren't represented with escape codes)
  
 */
 
 #[tokio::main(flavor = "multi_thread", worker_threads = 2)]
 async fn main() -> io::Result<()> {
+    // before the screen's taken over, so the error can be read
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("couldn't load config.json: {}", err);
+            std::process::exit(1);
+        },
+    };
+    let habit_log = match HabitLog::load() {
+        Ok(habit_log) => habit_log,
+        Err(err) => {
+            eprintln!("couldn't load habits.json: {}", err);
+            std::process::exit(1);
+        },
+    };
+    let theme = match themes::init(&config) {
+        Ok(theme) => theme,
+        Err(err) => {
+            eprintln!("couldn't load the theme: {}", err);
+            std::process::exit(1);
+        },
+    };
+    
     // this runtime is implemented in a way where blocking tasks/blocking thread sleeps don't block others tasks from running
     // each task gets its own thread so blocking is safe unless the section requires a safe/soft exit instead of a hard drop
     enableMouseCapture().await;
     enable_raw_mode()?;
     
     // starting the app
-    let mut app = App::new();
+    let mut app = App::new(config, habit_log, theme);
     app.run().await;
+    let metrics = app.renderer.GetMetrics();
+    drop(app);  // putting the screen back
     
     disableMouseCapture().await;
     disable_raw_mode()?;
     
+    if std::env::var_os("JOURNAL_RENDER_METRICS").is_some() {
+        println!("render: {}", metrics);
+    }
+    
     Ok(())
 }
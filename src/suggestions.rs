@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use crate::entries::{FieldValue, Log, Logs};
+
+// how many days old a use has to be before it only counts half as much
+const HALF_LIFE_DAYS: f64 = 30.0;
+
+// everything ever written into one kind of list (events, foods or a list field), for autocompleting new items
+// items are ranked by how often they were logged, with older uses counting for less
+#[derive(Default)]
+pub struct History {
+    // (item as most recently written, score), best first
+    items: Vec<(String, f64)>,
+}
+
+impl History {
+    // gathers the items the getter finds in each log
+    pub fn new<'a>(logs: &'a Logs, get_items: impl Fn(&'a Log) -> Vec<&'a str>) -> Self {
+        let today = chrono::Local::now().date_naive();
+        // lowercase item -> (item as written, score, the day it was last written)
+        let mut scores: HashMap<String, (String, f64, Option<chrono::NaiveDate>)> = HashMap::new();
+        for log in logs.iter() {
+            let date = log.get_naive_date();
+            // entries without a readable date are treated as a few months old
+            let age = date.map_or(HALF_LIFE_DAYS * 4.0, |date| (today - date).num_days().max(0) as f64);
+            for item in get_items(log) {
+                let item = item.trim();
+                if item.is_empty() {  continue;  }
+                let entry = scores.entry(item.to_lowercase()).or_insert_with(|| (item.to_string(), 0.0, date));
+                entry.1 += 0.5f64.powf(age / HALF_LIFE_DAYS);
+                if date >= entry.2 {
+                    entry.0 = item.to_string();
+                    entry.2 = date;
+                }
+            }
+        }
+        let mut items: Vec<(String, f64)> = scores.into_values().map(|(item, score, _)| (item, score)).collect();
+        items.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        History { items }
+    }
+
+    pub fn events(logs: &Logs) -> Self {
+        Self::new(logs, |log| log.events.iter().flatten().map(String::as_str).collect())
+    }
+
+    pub fn foods(logs: &Logs) -> Self {
+        Self::new(logs, |log| log.food.iter().flatten().map(String::as_str).collect())
+    }
+
+    // the items of a list custom field, matched by the field's name
+    pub fn custom_list(logs: &Logs, name: &str) -> Self {
+        Self::new(logs, |log| {
+            log.custom_fields.iter().flatten()
+                .filter(|value| value.name == name)
+                .flat_map(|value| match &value.value {
+                    FieldValue::List(items) => items.iter().map(String::as_str).collect(),
+                    _ => vec![],
+                })
+                .collect()
+        })
+    }
+
+    // The best items matching what's been typed so far, leaving out any already in the list.
+    // Items starting with the text come before ones that only contain it, case is ignored.
+    pub fn get_suggestions(&self, typed: &str, existing: &[String], count: usize) -> Vec<&str> {
+        let typed = typed.trim().to_lowercase();
+        let existing: Vec<String> = existing.iter().map(|item| item.trim().to_lowercase()).collect();
+        let mut starting = vec![];
+        let mut containing = vec![];
+        for (item, _) in &self.items {
+            let lowercase = item.to_lowercase();
+            if existing.contains(&lowercase) {  continue;  }
+            if lowercase.starts_with(&typed) {
+                starting.push(item.as_str());
+            } else if lowercase.contains(&typed) {
+                containing.push(item.as_str());
+            }
+        }
+        starting.append(&mut containing);
+        starting.truncate(count);
+        starting
+    }
+}
@@ -0,0 +1,299 @@
+// Drives the whole app with scripted input, rendering into a headless backend and checking the screen
+// (text and colors) against the snapshots in src/snapshots/
+// Run with UPDATE_SNAPSHOTS=1 to write new snapshots after an intended change, then look over the diff
+
+use crate::*;
+use crate::TermRender::backend::HeadlessBackend;
+
+// the theme and locale are global, so only one app runs at a time
+static APP_LOCK: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+
+// entries without a time only show their written out date, so nothing depends on the clock
+static LOGS: &str = r#"[
+    {"entry_date": "Monday, the 1st of September, 2025", "entry_title": "First day", "entry_text": "Started the journal."},
+    {"entry_date": "Tuesday, the 2nd of September, 2025", "entry_title": "Rain", "entry_text": "It rained all day.\nStayed in and read.", "events": ["reading"]},
+    {"entry_date": "Wednesday, the 3rd of September, 2025", "entry_title": "漢字 practice", "entry_text": "Wide characters take up two cells."}
+]"#;
+
+struct Harness {
+    app: App,
+    backend: HeadlessBackend,
+    parser: vte::Parser,
+    key_parser: KeyParser,
+    _lock: parking_lot::MutexGuard<'static, ()>,
+}
+
+impl Harness {
+    async fn new(size: (u16, u16)) -> Self {
+        let lock = APP_LOCK.lock();
+        let config = Config {
+            locale: Some(String::from("en")),
+            theme: Some(String::from("dark")),
+            ..Config::default()
+        };
+        let backend = HeadlessBackend::new(size);
+        let renderer = TermRender::App::WithBackend(Box::new(backend.clone()));
+        let theme = themes::init(&config).unwrap();
+        let mut app = App::from_parts(renderer, serde_json::from_str(LOGS).unwrap(), config, HabitLog::default(), theme);
+        app.persist = false;
+        let mut harness = Harness {
+            app,
+            backend,
+            parser: vte::Parser::new(),
+            key_parser: KeyParser::new(),
+            _lock: lock,
+        };
+        harness.app.resize(size);
+        harness.frame(b"").await;
+        harness
+    }
+
+    // a frame with whatever was read from stdin (one key, escape sequence or mouse event at a time)
+    async fn frame(&mut self, bytes: &[u8]) {
+        self.key_parser.ClearEvents();
+        if !bytes.is_empty() {
+            self.key_parser.ParseBytes(&mut self.parser, bytes);
+        }
+        self.app.update(&self.key_parser).await;
+    }
+
+    // a left click, which takes a frame to press and another to release (positions count from 1)
+    async fn click(&mut self, column: u16, row: u16) {
+        self.frame(format!("\x1b[<0;{};{}M", column, row).as_bytes()).await;
+        self.frame(format!("\x1b[<0;{};{}m", column, row).as_bytes()).await;
+        self.frame(b"").await;
+    }
+
+    // where some text is on the screen (counting from 1, like the mouse)
+    fn find(&self, text: &str) -> (u16, u16) {
+        for (row, line) in self.backend.GetLines().iter().enumerate() {
+            if let Some(index) = line.find(text) {
+                let column = TermRender::GetDisplayWidth(&line[..index]);
+                return (column as u16 + 1, row as u16 + 1);
+            }
+        }
+        panic!("{:?} isn't on the screen", text);
+    }
+
+    // opens the editor on a log, as if its edit button were clicked
+    fn edit(&mut self, index: usize) {
+        self.app.selected = Some(index);
+        let hit = TermRender::MouseHit { window: String::from("EditButton"), position: (0, 0), line: 0, column: 0, onBorder: false };
+        self.app.on_edit_button_mouse(&hit, TermRender::MouseAction::Click);
+    }
+
+    // checks a window lies within the screen and, if it has room for one, that its border was drawn there
+    fn assert_window_drawn(&mut self, name: &str) {
+        let screen = self.backend.GetLines();
+        let window = self.app.renderer.GetWindowReferenceMut(String::from(name));
+        let (position, size) = (window.position, window.size);
+        let area = (self.app.area.width, self.app.area.height);
+        // positions count from 1
+        assert!(position.0 + size.0 <= area.0 + 1 && position.1 + size.1 <= area.1 + 1, "{} at {:?} ({:?}) is off the screen", name, position, size);
+        if size.0 < 2 || size.1 < 2 {  return;  }
+        let cell = |column: u16, row: u16| screen[row as usize - 1].chars().nth(column as usize - 1);
+        let (right, bottom) = (position.0 + size.0 - 1, position.1 + size.1 - 1);
+        assert_eq!(cell(position.0, position.1), Some('┌'), "{}'s top left corner", name);
+        assert_eq!(cell(right, position.1), Some('┐'), "{}'s top right corner", name);
+        assert_eq!(cell(position.0, bottom), Some('└'), "{}'s bottom left corner", name);
+        assert_eq!(cell(right, bottom), Some('┘'), "{}'s bottom right corner", name);
+    }
+
+    fn snapshot(&self) -> String {
+        // whatever was written has to add up to what the renderer thinks is on the screen
+        assert_eq!(Some(&self.backend.GetScreen()), self.app.renderer.GetScreen());
+        self.backend.GetSnapshot()
+    }
+}
+
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = format!("{}/src/snapshots/{}.snap", env!("CARGO_MANIFEST_DIR"), name);
+    let actual = format!("{}\n", actual);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(format!("{}/src/snapshots", env!("CARGO_MANIFEST_DIR"))).unwrap();
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("no snapshot at {} (run with UPDATE_SNAPSHOTS=1 to write it)\n{}", path, actual));
+    if expected != actual {
+        panic!("the screen doesn't match {}\n--- expected\n{}--- actual\n{}", path, expected, actual);
+    }
+}
+
+#[tokio::test]
+async fn opens_on_the_list_of_logs() {
+    let harness = Harness::new((100, 24)).await;
+    assert_snapshot("list_of_logs", &harness.snapshot());
+}
+
+#[tokio::test]
+async fn clicking_a_log_opens_it() {
+    let mut harness = Harness::new((100, 24)).await;
+    let (column, row) = harness.find("Rain");
+    harness.click(column, row).await;
+    assert_snapshot("open_log", &harness.snapshot());
+
+    // clicking it again closes it
+    harness.click(column, row).await;
+    assert_snapshot("list_of_logs", &harness.snapshot());
+}
+
+#[tokio::test]
+async fn switching_themes_redraws_everything() {
+    let mut harness = Harness::new((100, 24)).await;
+    let dark = harness.snapshot();
+    harness.frame(&[20]).await;  // control + t
+    assert_eq!(harness.app.theme, "light");
+    assert_snapshot("list_of_logs_light", &harness.snapshot());
+
+    harness.frame(&[20]).await;
+    assert_eq!(harness.app.theme, "dark");
+    assert_eq!(harness.snapshot(), dark);
+}
+
+#[tokio::test]
+async fn resizing_moves_the_windows() {
+    let mut harness = Harness::new((100, 24)).await;
+    harness.backend.Resize((80, 20));
+    harness.app.resize((80, 20));
+    harness.frame(b"").await;
+    assert_snapshot("list_of_logs_small", &harness.snapshot());
+}
+
+#[tokio::test]
+async fn menus_open_in_tiny_terminals() {
+    // the menus are laid out from the terminal's size, which can be smaller than they'd like
+    for size in [(20, 8), (45, 12), (60, 14)] {
+        let mut harness = Harness::new(size).await;
+        harness.app.creator_button = Some(CreatorButton::new(&harness.app.config, &harness.app.logs));
+        harness.frame(b"").await;
+        harness.assert_window_drawn("CreatorMenu");
+        harness.click(size.0 - 3, size.1 - 3).await;
+        harness.snapshot();
+        harness.app.creator_button = None;
+        harness.app.habit_tracker = Some(HabitTracker::new(&harness.app.area));
+        harness.frame(b"").await;
+        harness.assert_window_drawn("HabitTracker");
+        harness.snapshot();
+        harness.app.habit_tracker = None;
+        harness.app.analysis = Some(AnalysisView::new());
+        harness.frame(b"").await;
+        harness.assert_window_drawn("Analysis");
+        harness.assert_window_drawn("Logs");
+        // every row is still the width of the screen, so nothing was drawn past its edge
+        let screen = harness.snapshot();
+        assert_eq!(screen.lines().count(), size.1 as usize);
+        for line in harness.backend.GetLines() {
+            assert_eq!(TermRender::GetDisplayWidth(&line), size.0 as usize);
+        }
+    }
+}
+
+#[tokio::test]
+async fn editing_keeps_values_the_config_no_longer_has() {
+    let mut harness = Harness::new((100, 24)).await;
+    harness.app.logs = serde_json::from_str(r#"[{
+        "entry_date": "Monday, the 1st of September, 2025", "entry_title": "Old", "entry_text": "",
+        "mood": {"quality": 7, "description": "", "reason": null, "dimensions": [{"name": "focus", "value": 3}]},
+        "custom_fields": [{"name": "weather", "value": {"text": "sunny"}}]
+    }]"#).unwrap();
+    harness.edit(0);
+    let mut button = harness.app.creator_button.take().unwrap();
+    button.create(&mut harness.app.logs, Some(0));
+
+    let log = &harness.app.logs[0];
+    assert_eq!(log.mood.as_ref().unwrap().get_dimension("focus"), Some(3));
+    let custom_fields = log.custom_fields.as_ref().unwrap();
+    assert_eq!(custom_fields.len(), 1);
+    assert_eq!(custom_fields[0].name, "weather");
+    assert_eq!(custom_fields[0].value, entries::FieldValue::Text(String::from("sunny")));
+}
+
+#[tokio::test]
+async fn editing_keeps_when_an_entry_was_written() {
+    let mut harness = Harness::new((100, 24)).await;
+    // one date that can be read back and one that can't
+    harness.app.logs = serde_json::from_str(r#"[
+        {"entry_date": "sometime in 2019", "entry_title": "Lost", "entry_text": ""},
+        {"entry_date": "Monday, the 1st of September, 2025", "entry_title": "Old", "entry_text": ""}
+    ]"#).unwrap();
+    for index in 0..2 {
+        let date = harness.app.logs[index].entry_date.clone();
+        harness.edit(index);
+        let mut button = harness.app.creator_button.take().unwrap();
+        button.create(&mut harness.app.logs, Some(index));
+
+        let log = &harness.app.logs[button.saved_index.unwrap()];
+        assert_eq!(log.entry_date, date);
+        assert!(log.entry_time.is_none());
+    }
+}
+
+#[tokio::test]
+async fn the_analysis_scrolls_through_every_item() {
+    use chrono::TimeZone;
+    let mut harness = Harness::new((100, 24)).await;
+    // more items than fit, each logged on some days and not others
+    let mut logs = Logs::new();
+    for day in 0..30 {
+        let mut log = entries::Log::new(String::new(), String::new());
+        log.set_time(chrono::Local.with_ymd_and_hms(2025, 9, 1, 12, 0, 0).unwrap() + chrono::TimeDelta::days(day));
+        log.mood = Some(entries::Mood { quality: (day % 7 + 1) as usize, description: String::new(), reason: None, dimensions: None });
+        for item in 0..20 {
+            if day % (item % 5 + 2) == 0 {
+                log.add_event(format!("event {}", item));
+            }
+        }
+        logs.insert_sorted(log);
+    }
+    harness.app.logs = logs;
+    harness.app.analysis = Some(AnalysisView::new());
+    harness.frame(b"").await;
+    let text = harness.app.analysis.as_ref().unwrap().get_window_text(&harness.app.logs);
+    let window = harness.app.renderer.GetWindowReferenceMut(String::from("Analysis"));
+    assert!(text.len() > window.GetViewportSize().1);
+    assert_eq!(window.GetContentSize().1, text.len());
+
+    harness.frame(b"\x1b[B").await;
+    harness.frame(b"\x1b[B").await;
+    let window = harness.app.renderer.GetWindowReferenceMut(String::from("Analysis"));
+    assert_eq!(window.GetScroll().1, 2);
+    harness.frame(b"\x1b[A").await;
+    let window = harness.app.renderer.GetWindowReferenceMut(String::from("Analysis"));
+    assert_eq!(window.GetScroll().1, 1);
+}
+
+#[test]
+fn a_theme_that_cant_be_read_is_an_error() {
+    let config = Config { theme: Some(String::from("no such theme")), ..Config::default() };
+    let err = themes::init(&config).unwrap_err();
+    assert!(err.starts_with("themes/no such theme.json: "), "{}", err);
+}
+
+#[tokio::test]
+async fn wide_names_keep_the_columns_lined_up() {
+    let harness = Harness::new((100, 24)).await;
+    let habit = |name: &str| config::HabitDefinition { name: name.to_string(), kind: config::HabitKind::Check, target: 1 };
+    // short and long, narrow and wide (two cells a character)
+    let habits = [habit("walk"), habit("水を飲む"), habit("a habit with a very long name"), habit("毎日日本語を勉強する習慣")];
+    let tracker = HabitTracker::new(&harness.app.area);
+    let text = tracker.get_window_text(&harness.app.area, &habits, &HabitLog::default());
+    // below the week days, the dates and a gap
+    let rows = &text[4..4 + habits.len()];
+    assert!(rows.iter().all(|row| row.Size() == rows[0].Size()));
+
+    let mut logs = Logs::new();
+    for day in 0..10 {
+        let mut log = entries::Log::new(String::new(), String::new());
+        log.set_time(chrono::Local::now() - chrono::TimeDelta::days(day));
+        log.mood = Some(entries::Mood { quality: (day % 3 + 1) as usize, description: String::new(), reason: None, dimensions: None });
+        log.add_event(String::from(["walk", "水を飲む"][day as usize % 2]));
+        log.add_food(String::from(["毎日日本語を勉強する習慣の食べ物", "toast"][day as usize % 2]));
+        logs.insert_sorted(log);
+    }
+    let text = AnalysisView::new().get_window_text(&logs);
+    let rows: Vec<_> = text.iter().filter(|row| row.Size() > 0).skip(2).take(4).collect();
+    assert_eq!(rows.len(), 4);
+    assert!(rows.iter().all(|row| row.Size() == rows[0].Size()));
+}
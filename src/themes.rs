@@ -0,0 +1,64 @@
+use crate::TermRender::theme::Theme;
+use crate::config::Config;
+
+// the themes built into the binary; others can be dropped in as themes/<name>.json
+static SHIPPED: &[(&str, &str)] = &[
+    ("dark", include_str!("../themes/dark.json")),
+    ("light", include_str!("../themes/light.json")),
+];
+
+fn load(name: &str) -> Result<Theme, String> {
+    if let Some((_, theme)) = SHIPPED.iter().find(|(shipped, _)| *shipped == name) {
+        return Theme::FromJson(theme);
+    }
+    let path = format!("themes/{}.json", name);
+    Theme::FromFile(&path).map_err(|error| format!("{}: {}", path, error))
+}
+
+// the shipped themes followed by any dropped in, in the order they're switched through
+pub fn get_names() -> Vec<String> {
+    let mut names: Vec<String> = SHIPPED.iter().map(|(name, _)| name.to_string()).collect();
+    let mut dropped_in = vec![];
+    for entry in std::fs::read_dir("themes").into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|extension| extension == "json")
+            && let Some(name) = path.file_stem().and_then(|stem| stem.to_str())
+            && !names.iter().any(|shipped| shipped == name)
+        {
+            dropped_in.push(name.to_string());
+        }
+    }
+    dropped_in.sort();
+    names.append(&mut dropped_in);
+    names
+}
+
+// switches to the named theme, keeping the current one if it can't be read
+pub fn set(name: &str) -> bool {
+    match load(name) {
+        Ok(theme) => {
+            Theme::Set(theme);
+            true
+        },
+        Err(_) => false,
+    }
+}
+
+// the theme from the config (dark if there isn't one), returning its name
+// one that's named but can't be read is an error, rather than quietly being dark
+pub fn init(config: &Config) -> Result<String, String> {
+    let name = config.theme.clone().unwrap_or_else(|| String::from("dark"));
+    Theme::Set(load(&name)?);
+    Ok(name)
+}
+
+// the theme after the current one, skipping any that can't be read
+pub fn next(current: &str) -> String {
+    let names = get_names();
+    let start = names.iter().position(|name| name == current).map_or(0, |index| index + 1);
+    for offset in 0..names.len() {
+        let name = &names[(start + offset) % names.len()];
+        if set(name) {  return name.clone();  }
+    }
+    current.to_string()
+}